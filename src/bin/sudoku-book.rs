@@ -0,0 +1,76 @@
+use sudoku_rs::{*, utils::{InputRecord, read_records}};
+
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "sudoku-book")]
+struct Options {
+  /// Input puzzle file, as written by `sudoku-gen --generate-questions` (json
+  /// or ndjson). Records without an answer are solved
+  #[structopt(short = "i", long, name = "INPUT")]
+  input: String,
+
+  /// Output PDF file name
+  #[structopt(short = "o", long, name = "OUTPUT")]
+  output: String,
+
+  /// Title shown at the top of every page
+  #[structopt(long, default_value = "Sudoku", name = "TITLE")]
+  title: String,
+
+  /// Number of puzzles per page
+  #[structopt(long, default_value = "4", name = "#PUZZLES")]
+  per_page: usize,
+
+  /// Number of answers per page in the answer section
+  #[structopt(long, default_value = "6", name = "#ANSWERS")]
+  answers_per_page: usize,
+
+  /// Leave out the answer section
+  #[structopt(long)]
+  no_answers: bool,
+
+  /// Order the puzzles from the easiest to the hardest
+  #[structopt(long)]
+  sort_by_difficulty: bool,
+}
+
+fn execute_records<B: Board>(records: &[InputRecord], options: &Options) -> Result<(), String> {
+  let mut puzzles = vec![];
+  for (i, record) in records.iter().enumerate() {
+    let puzzle = B::from_u8_vec(&record.question);
+    let solution = match &record.answer {
+      Some(answer) => B::from_u8_vec(answer),
+      None => puzzle.solve_with_ctx(SolvingContext::deterministic()).next().ok_or_else(|| format!("Record {} has no solution", i))?,
+    };
+    puzzles.push((puzzle, solution));
+  }
+  if options.sort_by_difficulty {
+    puzzles.sort_by_cached_key(|(puzzle, _)| puzzle.difficulty());
+  }
+
+  let book = PuzzleBook::new(&options.title)
+    .puzzles_per_page(options.per_page)
+    .answers_per_page(options.answers_per_page)
+    .answers(!options.no_answers);
+  std::fs::write(&options.output, book.render(&puzzles)).map_err(|_| "Cannot write output file")?;
+  eprintln!("Wrote {} puzzles to {}", puzzles.len(), options.output);
+  Ok(())
+}
+
+fn main() -> Result<(), String> {
+  let options = Options::from_args();
+  let records = read_records(&options.input)?;
+  for record in &records {
+    record.check()?;
+  }
+  match records.first().map(InputRecord::size) {
+    None => Err("Input file contains no record".to_string()),
+    Some(size) if records.iter().any(|record| record.size() != size) => {
+      Err("All the records in the input file must have the same board size".to_string())
+    },
+    Some(4) => execute_records::<Board4x4>(&records, &options),
+    Some(9) => execute_records::<Board9x9>(&records, &options),
+    Some(size) => Err(format!("Unsupported board size {}", size)),
+  }
+}
@@ -0,0 +1,87 @@
+use sudoku_rs::{*, utils::{OutputOptions, InputRecord, read_records}};
+
+use structopt::StructOpt;
+use std::collections::HashSet;
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "sudoku-canon")]
+struct Options {
+  /// Input puzzle file, as written by `sudoku-gen` (json, ndjson, binary, or
+  /// CSV), or the puzzle file of a desktop sudoku tool
+  #[structopt(short = "i", long, name = "INPUT")]
+  input: String,
+
+  /// Output the canonical form of each record instead of the record itself
+  #[structopt(long)]
+  canonicalize: bool,
+
+  /// Keep records that are isomorphic to an earlier record
+  #[structopt(long)]
+  keep_duplicates: bool,
+
+  /// Output json file name. If not specified, the output will be printed on screen
+  #[structopt(short = "o", long, name = "OUTPUT")]
+  output: Option<String>,
+
+  #[structopt(flatten)]
+  format: OutputOptions,
+}
+
+fn execute_records<B: Board>(records: &[InputRecord], options: &Options) -> Result<(), String> {
+  let mut output = options.format.output(&options.output)?;
+  let mut seen = HashSet::new();
+  let mut num_records = 0;
+
+  // Initialize output
+  output.output_init()?;
+
+  for record in records {
+    let question = B::from_u8_vec(&record.question);
+    let answer = record.answer.as_ref().map(|answer| B::from_u8_vec(answer));
+
+    // Records are identified by the canonical form of their question
+    let (canonical, transform) = question.canonical_form();
+    if !seen.insert(canonical.clone()) && !options.keep_duplicates {
+      continue;
+    }
+
+    // Output the separator
+    if num_records > 0 {
+      output.output_separator()?;
+    }
+    num_records += 1;
+
+    // Output the record, transformed into canonical form if asked to
+    let (question, answer) = if options.canonicalize {
+      (canonical, answer.map(|answer| answer.transform(&transform)))
+    } else {
+      (question, answer)
+    };
+    match answer {
+      Some(answer) => output.output_board_with_solution(&question, &answer)?,
+      None => output.output_board(&question)?,
+    }
+  }
+
+  // Finish output
+  output.output_finish()?;
+  eprintln!("Read {} records, {} of them are essentially different", records.len(), seen.len());
+  Ok(())
+}
+
+fn main() -> Result<(), String> {
+  let options = Options::from_args();
+  let records = read_records(&options.input)?;
+  for record in &records {
+    record.check()?;
+  }
+  match records.first().map(InputRecord::size) {
+    None => Err("Input file contains no record".to_string()),
+    Some(size) if records.iter().any(|record| record.size() != size) => {
+      Err("All the records in the input file must have the same board size".to_string())
+    },
+    Some(4) => execute_records::<Board4x4>(&records, &options),
+    Some(9) => execute_records::<Board9x9>(&records, &options),
+    Some(size) => Err(format!("Unsupported board size {}", size)),
+  }
+}
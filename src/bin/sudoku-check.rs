@@ -0,0 +1,77 @@
+use sudoku_rs::{*, utils::{Output, OutputFormat, EntryRecord, read_entry_records}};
+
+use serde_json::json;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "sudoku-check")]
+struct Options {
+  /// Input json file, containing `{"puzzle": board, "entries": board}` records
+  /// (a single record, a json array, or ndjson)
+  #[structopt(short = "i", long, name = "INPUT")]
+  input: String,
+
+  /// Output json file name. If not specified, the output will be printed on screen
+  #[structopt(short = "o", long, name = "OUTPUT")]
+  output: Option<String>,
+
+  /// Output format, either `json` or `ndjson`. With `ndjson` every record is
+  /// written on its own line and flushed right away
+  #[structopt(long, default_value = "json", name = "FORMAT")]
+  output_format: OutputFormat,
+}
+
+fn report_to_json_str(report: &EntryReport) -> String {
+  let slots = |slots: &[Slot]| slots.iter().map(|(i, j)| json!([i, j])).collect::<Vec<_>>();
+  json!({
+    "wrong": slots(&report.wrong),
+    "entries": report.entries.len(),
+    "empty": report.empty.len(),
+    "unique": report.unique,
+    "solvable": report.solvable,
+    "solved": report.is_solved(),
+  }).to_string()
+}
+
+fn check_record<B: Board>(record: &EntryRecord) -> Result<EntryReport, String> {
+  let puzzle = B::try_from_u8_vec(&record.puzzle)?;
+  puzzle.check_entries(&B::try_from_u8_vec(&record.entries)?)
+}
+
+fn main() -> Result<(), String> {
+  let options = Options::from_args();
+  if matches!(options.output_format, OutputFormat::Binary | OutputFormat::Npy | OutputFormat::Csv) {
+    return Err("Reports cannot be written in the binary, npy, or csv formats".to_string());
+  }
+  let records = read_entry_records(&options.input)?;
+  let mut output = Output::new_with_format(&options.output, options.output_format)?;
+  let mut num_wrong = 0;
+
+  // Initialize output
+  output.output_init()?;
+
+  for (i, record) in records.iter().enumerate() {
+    let report = match record.puzzle.len() {
+      16 => check_record::<Board4x4>(record),
+      81 => check_record::<Board9x9>(record),
+      _ => Err("Boards must contain 16 or 81 elements".to_string()),
+    }.map_err(|err| format!("Record {}: {}", i, err))?;
+    if !report.wrong.is_empty() {
+      num_wrong += 1;
+    }
+
+    // Output the separator
+    if i > 0 {
+      output.output_separator()?;
+    }
+    match output.format() {
+      OutputFormat::NdJson => output.write_line(&report_to_json_str(&report))?,
+      _ => output.write(&report_to_json_str(&report))?,
+    }
+  }
+
+  // Finish output
+  output.output_finish()?;
+  eprintln!("Checked {} records, {} of them with wrong entries", records.len(), num_wrong);
+  Ok(())
+}
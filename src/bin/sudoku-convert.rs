@@ -0,0 +1,80 @@
+use sudoku_rs::{*, utils::{BinaryReader, OutputOptions, InputRecord, is_binary, read_records}};
+
+use structopt::StructOpt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "sudoku-convert")]
+struct Options {
+  /// Input puzzle file, either json, ndjson, binary, or CSV (as written by
+  /// `sudoku-gen`), or the puzzle file of a desktop sudoku tool. The format is
+  /// detected from the content
+  #[structopt(short = "i", long, name = "INPUT")]
+  input: String,
+
+  /// Output file name. If not specified, the output will be printed on screen
+  #[structopt(short = "o", long, name = "OUTPUT")]
+  output: Option<String>,
+
+  #[structopt(flatten)]
+  format: OutputOptions,
+}
+
+fn execute_records<B: Board>(records: impl Iterator<Item = Result<InputRecord, String>>, options: &Options) -> Result<(), String> {
+  let mut output = options.format.output(&options.output)?;
+  let mut num_records = 0;
+
+  // Initialize output
+  output.output_init()?;
+
+  for (i, record) in records.enumerate() {
+    let record = record.map_err(|err| format!("Record {}: {}", i, err))?;
+    record.check().map_err(|err| format!("Record {}: {}", i, err))?;
+    if record.size() != B::size() {
+      return Err("All the records in the input file must have the same board size".to_string());
+    }
+
+    // Output the separator
+    if i > 0 {
+      output.output_separator()?;
+    }
+    let question = B::from_u8_vec(&record.question);
+    match &record.answer {
+      Some(answer) => output.output_board_with_solution(&question, &B::from_u8_vec(answer))?,
+      None => output.output_board(&question)?,
+    }
+    num_records += 1;
+  }
+
+  // Finish output
+  output.output_finish()?;
+  eprintln!("Converted {} records", num_records);
+  Ok(())
+}
+
+fn execute(size: usize, records: impl Iterator<Item = Result<InputRecord, String>>, options: &Options) -> Result<(), String> {
+  match size {
+    4 => execute_records::<Board4x4>(records, options),
+    9 => execute_records::<Board9x9>(records, options),
+    size => Err(format!("Unsupported board size {}", size)),
+  }
+}
+
+fn main() -> Result<(), String> {
+  let options = Options::from_args();
+  let file = File::open(&options.input).map_err(|_| "Cannot open input file")?;
+  let mut file = BufReader::new(file);
+
+  // Binary files are streamed record by record, since they can be very large
+  if is_binary(file.fill_buf().map_err(|_| "Cannot read from input file")?) {
+    let reader = BinaryReader::new(file)?;
+    let size = reader.header().board_size;
+    return execute(size, reader, &options);
+  }
+  let records = read_records(&options.input)?;
+  match records.first().map(InputRecord::size) {
+    None => Err("Input file contains no record".to_string()),
+    Some(size) => execute(size, records.into_iter().map(Ok), &options),
+  }
+}
@@ -1,7 +1,15 @@
-use sudoku_rs::{*, utils::Output};
+use sudoku_rs::{*, utils::{Output, OutputFormat, OutputOptions, RenderOptions}};
 
 use structopt::StructOpt;
-use rand::{Rng, SeedableRng, rngs::StdRng};
+use rand::Rng;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "sudoku-gen")]
@@ -23,6 +31,31 @@ struct Options {
   #[structopt(long, name = "SEED")]
   seed: Option<u64>,
 
+  /// Sample the solutions uniformly at random among all the complete grids,
+  /// instead of enumerating them with the (biased) randomized search. Requires
+  /// `--num-solutions`
+  #[structopt(long)]
+  uniform: bool,
+
+  /// Number of Markov chain steps between two uniformly sampled solutions
+  #[structopt(long, name = "#STEPS", default_value = "20")]
+  mixing_steps: usize,
+
+  /// Stop searching for solutions after the given number of seconds
+  #[structopt(long, name = "SECONDS")]
+  timeout: Option<f64>,
+
+  /// File the state of the generation is saved to when it is interrupted
+  /// (Ctrl-C or timeout), so that it can be resumed with `--resume`
+  #[structopt(long, name = "CHECKPOINT", default_value = "sudoku-gen.checkpoint.json")]
+  checkpoint: String,
+
+  /// Resume an interrupted generation from its checkpoint file. The other
+  /// options need to be the same as in the interrupted run; new records are
+  /// appended to its output
+  #[structopt(long, name = "CHECKPOINT_FILE")]
+  resume: Option<String>,
+
   /// If specified, will generate questions instead of full solutions
   #[structopt(long)]
   generate_questions: bool,
@@ -47,94 +80,299 @@ struct Options {
   #[structopt(long, name = "MAX_#UNKNOWNS", default_value = "12")]
   max_num_unknowns: usize,
 
+  /// Number of random isomorphs (boards equivalent under sudoku symmetries)
+  /// to emit along with each generated record
+  #[structopt(long, default_value = "0", name = "K")]
+  augment: usize,
+
+  /// Never output the same question (or the same solution when not generating
+  /// questions) twice in one run
+  #[structopt(long)]
+  dedup: bool,
+
+  /// Include in every question record the steps solving it, as a `"t"` array
+  /// of `[row, column, value, reason]` steps, the reason being `given`,
+  /// `naked_single`, `hidden_single`, `guess`, or `backtrack`. Requires
+  /// `--generate-questions` and the json or ndjson output formats. The trace
+  /// ends at the answer of the record, guesses leading to other solutions being
+  /// taken back
+  #[structopt(long)]
+  emit_trace: bool,
+
   /// Output json file name. If not specified, the output will be printed on screen
   #[structopt(short = "o", long, name = "FILE")]
   output: Option<String>,
+
+  /// Also draw every record as SVG images in the given directory: a question
+  /// and an answer image per generated question, or an answer image alone
+  #[structopt(long, name = "DIR")]
+  svg_dir: Option<String>,
+
+  #[structopt(flatten)]
+  format: OutputOptions,
+
+  #[structopt(flatten)]
+  render: RenderOptions,
 }
 
-fn output_solution<B: Board>(
-  output: &mut Output,
-  rng: &mut StdRng,
-  options: &Options,
-  i: usize,
-  solution: B,
-) -> Result<(), String> {
+/// Number of attempts at generating a question not seen before when `--dedup`
+/// is specified
+const MAX_DEDUP_ATTEMPTS : usize = 100;
 
-  // Check if we need to generate questions
-  if options.generate_questions {
+/// The state of a generation run
+struct Generator<'a, B: Board> {
+  options: &'a Options,
+  output: Output,
+  rng: SolverRng,
+  num_solutions: usize,
+  num_records: usize,
+  seen: HashSet<B>,
+}
 
-    // Find out the number of questions to generate
-    for j in 0..options.num_questions_per_solution {
+impl<'a, B: Board> Generator<'a, B> {
+  fn is_duplicate(&self, board: &B) -> bool {
+    self.options.dedup && self.seen.contains(board)
+  }
 
-      // Output the separator
-      if i > 0 || j > 0 {
-        output.output_separator()?;
-      }
+  fn output_record(&mut self, question: Option<&B>, solution: &B) -> Result<(), String> {
 
-      // Find out the number
-      let num_unknowns = if options.random_num_unknowns {
-        rng.gen_range(options.min_num_unknowns, options.max_num_unknowns)
-      } else {
-        options.num_unknowns
-      };
+    // Skip the record if it has been output before
+    let key = question.unwrap_or(solution);
+    if self.is_duplicate(key) {
+      return Ok(());
+    }
+    if self.options.dedup {
+      self.seen.insert(key.clone());
+    }
 
-      // Generate
-      let mut question = solution.clone();
-      question.put_random_unknowns(num_unknowns);
+    // Output the separator
+    if self.num_records > 0 {
+      self.output.output_separator()?;
+    }
+    self.num_records += 1;
+    if let Some(dir) = &self.options.svg_dir {
+      write_svgs(dir, self.num_records, question, solution)?;
+    }
 
-      // Output the question & solution
-      output.output_board_with_solution(&question, &solution)?;
+    // Output the question & solution, or the solution alone
+    match question {
+      Some(question) if self.options.emit_trace => {
+        let trace = question.solve_with_trace_to(solution);
+        self.output.output_board_with_trace(question, solution, &trace.steps)
+      },
+      Some(question) => self.output.output_board_with_solution(question, solution),
+      None => self.output.output_board(solution),
+    }
+  }
+
+  fn output_augmented_records(&mut self, question: Option<&B>, solution: &B) -> Result<(), String> {
+
+    // Output the original record
+    self.output_record(question, solution)?;
+
+    // Output the isomorphs, with the same transform applied to question and solution
+    for _ in 0..self.options.augment {
+      let (solution, transform) = solution.random_isomorph_with_rng(&mut self.rng);
+      let question = question.map(|question| question.transform(&transform));
+      self.output_record(question.as_ref(), &solution)?;
     }
 
     Ok(())
-  } else {
+  }
 
-    // Output the separator
-    if i > 0 {
-      output.output_separator()?;
+  fn output_solution(&mut self, solution: B) -> Result<(), String> {
+    let options = self.options;
+
+    // Check if we need to generate questions
+    if options.generate_questions {
+
+      // Find out the number of questions to generate
+      for _ in 0..options.num_questions_per_solution {
+
+        // Try to generate a question that has not been output before
+        for _ in 0..MAX_DEDUP_ATTEMPTS {
+
+          // Find out the number
+          let num_unknowns = if options.random_num_unknowns {
+            self.rng.gen_range(options.min_num_unknowns, options.max_num_unknowns)
+          } else {
+            options.num_unknowns
+          };
+
+          // Generate
+          let mut question = solution.clone();
+          question.put_random_unknowns_with_rng(num_unknowns, &mut self.rng);
+
+          // Output the question & solution
+          if !self.is_duplicate(&question) {
+            self.output_augmented_records(Some(&question), &solution)?;
+            break;
+          }
+        }
+      }
+
+      Ok(())
+    } else {
+
+      // If not, directly output the solution
+      self.output_augmented_records(None, &solution)
+    }
+  }
+
+  fn output_solutions<I: Iterator<Item = B>>(&mut self, solution_iter: I) -> Result<(), String> {
+    match self.options.num_solutions {
+      Some(amount) => {
+        for solution in solution_iter.take(amount.saturating_sub(self.num_solutions)) {
+          self.num_solutions += 1;
+          self.output_solution(solution)?;
+        }
+      },
+      _ => {
+        for solution in solution_iter {
+          self.num_solutions += 1;
+          self.output_solution(solution)?;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Save the state of the generation, including the state of the search
+  fn save_checkpoint(&self, solutions: &BoardSolutions<B>) -> Result<(), String> {
+    let checkpoint = json!({
+      "board_size": B::size(),
+      "search": solutions.checkpoint(),
+      "rng": self.rng.to_json(),
+      "num_solutions": self.num_solutions,
+      "num_records": self.num_records,
+      "seen": self.seen.iter().map(Board::to_u8_vec).collect::<Vec<_>>(),
+    });
+    let mut file = File::create(&self.options.checkpoint).map_err(|_| "Cannot create checkpoint file")?;
+    file.write_all(checkpoint.to_string().as_bytes()).map_err(|_| "Cannot write checkpoint file".to_string())
+  }
+
+  /// Restore the state saved by `save_checkpoint`, returning the saved search
+  fn load_checkpoint(&mut self, checkpoint: &Value, ctx: SolvingContext) -> Result<BoardSolutions<B>, String> {
+    if checkpoint["board_size"].as_u64() != Some(B::size() as u64) {
+      return Err("Checkpoint was saved for another board size".to_string());
     }
+    self.rng = SolverRng::from_json(&checkpoint["rng"])?;
+    self.num_solutions = checkpoint["num_solutions"].as_u64().ok_or("Invalid checkpoint")? as usize;
+    self.num_records = checkpoint["num_records"].as_u64().ok_or("Invalid checkpoint")? as usize;
+    for board in checkpoint["seen"].as_array().ok_or("Invalid checkpoint")? {
+      let elements = serde_json::from_value::<Vec<u8>>(board.clone()).map_err(|_| "Invalid checkpoint")?;
+      self.seen.insert(B::from_u8_vec(&elements));
+    }
+    BoardSolutions::from_checkpoint(&checkpoint["search"], ctx)
+  }
+}
 
-    // If not, directly output the solution
-    output.output_board(&solution)
+/// Write `{index}-question.svg` and `{index}-answer.svg` in `dir`
+fn write_svgs<B: Board>(dir: &str, index: usize, question: Option<&B>, solution: &B) -> Result<(), String> {
+  let renderer = SvgRenderer::new();
+  let path = |name: &str| Path::new(dir).join(format!("{:06}-{}.svg", index, name));
+  if let Some(question) = question {
+    std::fs::write(path("question"), renderer.render(question)).map_err(|_| "Cannot write svg file")?;
   }
+  let answer = renderer.render_solution(question.unwrap_or(solution), solution);
+  std::fs::write(path("answer"), answer).map_err(|_| "Cannot write svg file".to_string())
+}
+
+fn read_checkpoint(filename: &str) -> Result<Value, String> {
+  let mut file = File::open(filename).map_err(|_| "Cannot open checkpoint file")?;
+  let mut contents = String::new();
+  file.read_to_string(&mut contents).map_err(|_| "Cannot read from checkpoint file")?;
+  serde_json::from_str(&contents).map_err(|_| "Cannot parse checkpoint file".to_string())
 }
 
 fn execute_on_board<B: Board>(board: B, options: Options) -> Result<(), String> {
-  let mut output = Output::new(&options.output)?;
-  let mut rng = match options.seed {
-    Some(seed) => StdRng::seed_from_u64(seed),
-    None => StdRng::from_entropy(),
+  if options.uniform && options.num_solutions.is_none() {
+    return Err("Uniform sampling never ends, --num-solutions is required".to_string());
+  }
+
+  if options.uniform && options.resume.is_some() {
+    return Err("Uniform sampling cannot be resumed from a checkpoint".to_string());
+  }
+
+  if options.emit_trace && !options.generate_questions {
+    return Err("Traces are only emitted for questions, --generate-questions is required".to_string());
+  }
+
+  if options.emit_trace && !matches!(options.format.output_format, OutputFormat::Json | OutputFormat::NdJson) {
+    return Err("Traces can only be written in the json and ndjson formats".to_string());
+  }
+
+  if let Some(dir) = &options.svg_dir {
+    std::fs::create_dir_all(dir).map_err(|_| "Cannot create svg directory")?;
+  }
+
+  let output = match options.resume {
+    Some(_) => options.format.resume(&options.output)?,
+    None => options.format.output(&options.output)?,
+  }.with_renderer(options.render.renderer());
+  let rng = match options.seed {
+    Some(seed) => SolverRng::seed_from_u64(seed),
+    None => SolverRng::from_entropy(),
   };
+  let mut generator = Generator { options: &options, output, rng, num_solutions: 0, num_records: 0, seen: HashSet::new() };
 
   // Initialize output
-  output.output_init()?;
+  generator.output.output_init()?;
 
-  // First get the solution iterator with a solving context constructed from options
-  let solution_iter = if options.no_random {
-    board.solve_with_ctx(SolvingContext::deterministic())
-  } else {
-    match options.seed {
-      Some(seed) => board.solve_with_ctx(SolvingContext::random_with_seed(seed)),
-      _ => board.solve()
-    }
-  };
+  // Ctrl-C and the timeout stop the generation, so that the output is
+  // finished and a checkpoint of the search can be saved
+  let cancel = Arc::new(AtomicBool::new(false));
+  let handler_cancel = cancel.clone();
+  ctrlc::set_handler(move || handler_cancel.store(true, Ordering::Relaxed)).map_err(|_| "Cannot set Ctrl-C handler")?;
+  let mut budget = SearchBudget::unlimited().with_cancel_flag(cancel);
+  if let Some(timeout) = options.timeout {
+    budget = budget.with_timeout(Duration::from_secs_f64(timeout));
+  }
 
-  // Generate solutions
-  match options.num_solutions {
-    Some(amount) => {
-      for (i, solution) in solution_iter.take(amount).enumerate() {
-        output_solution(&mut output, &mut rng, &options, i, solution)?;
-      }
-    },
-    _ => {
-      for (i, solution) in solution_iter.enumerate() {
-        output_solution(&mut output, &mut rng, &options, i, solution)?;
+  // Generate solutions, either with a uniform sampler or with a solving
+  // context constructed from options
+  let mut result = Ok(());
+  if options.uniform {
+    let sampler = match options.seed {
+      Some(seed) => UniformSampler::with_seed(seed),
+      _ => UniformSampler::new(),
+    };
+
+    // The budget is checked before every sample
+    let mut exhausted = None;
+    generator.output_solutions(sampler.steps_per_sample(options.mixing_steps).take_while(|_| {
+      exhausted = budget.check(0).err();
+      exhausted.is_none()
+    }))?;
+    if let Some(reason) = exhausted {
+      result = Err(format!("Generation stopped early: {}", reason));
+    }
+  } else {
+    let ctx = if options.no_random {
+      SolvingContext::deterministic()
+    } else {
+      match options.seed {
+        Some(seed) => SolvingContext::random_with_seed(seed),
+        _ => SolvingContext::random()
       }
+    };
+    let ctx = ctx.with_budget(budget);
+
+    // Start a new search or resume the saved one
+    let mut solution_iter = match &options.resume {
+      Some(filename) => generator.load_checkpoint(&read_checkpoint(filename)?, ctx)?,
+      None => board.solve_with_ctx(ctx),
+    };
+    generator.output_solutions(solution_iter.by_ref())?;
+    if let SearchStatus::BudgetExhausted(reason) = solution_iter.status() {
+      generator.save_checkpoint(&solution_iter)?;
+      result = Err(format!("Generation stopped early: {}. Resume with --resume {}", reason, options.checkpoint));
     }
   }
 
   // Finish output
-  output.output_finish()
+  generator.output.output_finish()?;
+  result
 }
 
 fn main() -> Result<(), String> {
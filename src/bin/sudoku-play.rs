@@ -0,0 +1,209 @@
+use sudoku_rs::{*, utils::{InputRecord, read_records}};
+
+use crossterm::{cursor, execute, queue, terminal};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use rand::{SeedableRng, rngs::StdRng};
+use structopt::StructOpt;
+use std::io::{self, Write};
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "sudoku-play")]
+struct Options {
+  /// Puzzle file written by `sudoku-gen` (json or ndjson). If not specified, a
+  /// new puzzle is generated
+  #[structopt(short = "i", long, name = "INPUT")]
+  input: Option<String>,
+
+  /// The index of the puzzle to play inside the input file
+  #[structopt(long, default_value = "0", name = "INDEX")]
+  index: usize,
+
+  /// The size of the generated puzzles, either 4 or 9
+  #[structopt(long, default_value = "9", name = "SIZE")]
+  board_size: usize,
+
+  /// The number of unknowns of the generated puzzles. Defaults to 50 for 9x9
+  /// and 10 for 4x4 puzzles
+  #[structopt(long, name = "#UNKNOWNS")]
+  num_unknowns: Option<usize>,
+
+  /// The random seed being used to generate puzzles
+  #[structopt(long, name = "SEED")]
+  seed: Option<u64>,
+}
+
+const HELP : &str = "arrows: move  digits: enter  0/del: clear  m: pencil mode  h: hint  u: undo  r: redo  n: new game  q: quit";
+
+struct Player<B> where B : Board {
+  game: Game<B>,
+  rng: StdRng,
+  num_unknowns: usize,
+  pencil_mode: bool,
+  message: String,
+}
+
+impl<B> Player<B> where B : Board {
+  /// Handle a key press. Returns `false` when the player quits
+  fn handle_key(&mut self, key: KeyEvent) -> bool {
+    self.message.clear();
+    match key.code {
+      KeyCode::Char('q') | KeyCode::Esc => return false,
+      KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+      KeyCode::Up => self.game.move_cursor(-1, 0),
+      KeyCode::Down => self.game.move_cursor(1, 0),
+      KeyCode::Left => self.game.move_cursor(0, -1),
+      KeyCode::Right => self.game.move_cursor(0, 1),
+      KeyCode::Char('0') | KeyCode::Delete | KeyCode::Backspace => { self.game.clear(); },
+      KeyCode::Char(c @ '1'..='9') => {
+        let digit = c as u8 - b'0';
+        let changed = if self.pencil_mode { self.game.toggle_mark(digit) } else { self.game.enter(digit) };
+        if !changed && self.game.is_given(&self.game.cursor()) {
+          self.message = "This slot holds a given".to_string();
+        }
+      },
+      KeyCode::Char('m') => self.pencil_mode = !self.pencil_mode,
+      KeyCode::Char('h') => {
+        self.message = match self.game.hint() {
+          Some((i, j)) => format!("Filled in slot ({}, {})", i, j),
+          None => "Nothing left to fill in".to_string(),
+        };
+      },
+      KeyCode::Char('u') => {
+        let undone = self.game.undo();
+        self.message = if undone { String::new() } else { "Nothing to undo".to_string() };
+      },
+      KeyCode::Char('r') => {
+        let redone = self.game.redo();
+        self.message = if redone { String::new() } else { "Nothing to redo".to_string() };
+      },
+      KeyCode::Char('n') => {
+        self.game = Game::random(self.num_unknowns, &mut self.rng);
+        self.message = "New game".to_string();
+      },
+      _ => {}
+    }
+    if self.game.is_solved() {
+      self.message = "Solved! Press n for a new game or q to quit".to_string();
+    }
+    true
+  }
+
+  fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+    let (size, block_size) = (B::size(), B::block_size());
+    let conflicts = self.game.conflicting_slots();
+    let separator = format!("+{}", format!("{}+", "-".repeat(3 * block_size)).repeat(block_size));
+    queue!(out, terminal::Clear(terminal::ClearType::All))?;
+    let mut y = 0;
+    for i in 0..size {
+      if i % block_size == 0 {
+        queue!(out, cursor::MoveTo(0, y), Print(&separator))?;
+        y += 1;
+      }
+      queue!(out, cursor::MoveTo(0, y), Print("|"))?;
+      for j in 0..size {
+        let slot = (i, j);
+        let digit = self.game.get(&slot);
+        let text = if digit == 0 { " . ".to_string() } else { format!(" {} ", digit) };
+        let color = if conflicts.contains(&slot) {
+          Color::Red
+        } else if self.game.is_given(&slot) {
+          Color::Reset
+        } else {
+          Color::Cyan
+        };
+        if slot == self.game.cursor() {
+          queue!(out, SetAttribute(Attribute::Reverse))?;
+        }
+        if self.game.is_given(&slot) {
+          queue!(out, SetAttribute(Attribute::Bold))?;
+        }
+        queue!(out, SetForegroundColor(color), Print(text), ResetColor, SetAttribute(Attribute::Reset))?;
+        if (j + 1) % block_size == 0 {
+          queue!(out, Print("|"))?;
+        }
+      }
+      y += 1;
+    }
+    queue!(out, cursor::MoveTo(0, y), Print(&separator))?;
+
+    // Status lines
+    let marks = self.game.marks(&self.game.cursor()).iter().map(ToString::to_string).collect::<Vec<_>>().join(" ");
+    let mode = if self.pencil_mode { "pencil" } else { "normal" };
+    queue!(out, cursor::MoveTo(0, y + 2), Print(format!("Mode: {}  Marks: {}", mode, marks)))?;
+    queue!(out, cursor::MoveTo(0, y + 3), Print(&self.message))?;
+    queue!(out, cursor::MoveTo(0, y + 5), Print(HELP))?;
+    out.flush()
+  }
+
+  fn run(&mut self) -> io::Result<()> {
+    let mut out = io::stdout();
+    loop {
+      self.draw(&mut out)?;
+      if let Event::Key(key) = event::read()? {
+        if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+          return Ok(());
+        }
+      }
+    }
+  }
+}
+
+fn play<B: Board>(record: Option<&InputRecord>, num_unknowns: usize, mut rng: StdRng) -> Result<(), String> {
+  // Puzzles without a stored solution need a unique one
+  let game = match record {
+    Some(InputRecord { question, answer: Some(answer), .. }) => Game::with_solution(B::from_u8_vec(question), B::from_u8_vec(answer))?,
+    Some(record) => Game::new(B::from_u8_vec(&record.question))?,
+    None => Game::random(num_unknowns, &mut rng),
+  };
+  let mut player = Player { game, rng, num_unknowns, pencil_mode: false, message: String::new() };
+
+  let _guard = TerminalGuard::new().map_err(|err| err.to_string())?;
+  player.run().map_err(|err| err.to_string())
+}
+
+/// Puts the terminal in raw mode on the alternate screen, and restores it when
+/// dropped, even when the game fails or panics
+struct TerminalGuard;
+
+impl TerminalGuard {
+  fn new() -> io::Result<Self> {
+    terminal::enable_raw_mode()?;
+    let guard = Self;
+    execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+    Ok(guard)
+  }
+}
+
+impl Drop for TerminalGuard {
+  fn drop(&mut self) {
+    let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+  }
+}
+
+fn main() -> Result<(), String> {
+  let options = Options::from_args();
+  let rng = match options.seed {
+    Some(seed) => StdRng::seed_from_u64(seed),
+    None => StdRng::from_entropy(),
+  };
+  let record = match &options.input {
+    Some(input) => {
+      let mut records = read_records(input)?;
+      if options.index >= records.len() {
+        return Err(format!("The input file only has {} records", records.len()));
+      }
+      let record = records.swap_remove(options.index);
+      record.check()?;
+      Some(record)
+    },
+    None => None,
+  };
+  let size = record.as_ref().map_or(options.board_size, InputRecord::size);
+  match size {
+    4 => play::<Board4x4>(record.as_ref(), options.num_unknowns.unwrap_or(10), rng),
+    9 => play::<Board9x9>(record.as_ref(), options.num_unknowns.unwrap_or(50), rng),
+    _ => Err("Board size must be 4 or 9".to_string()),
+  }
+}
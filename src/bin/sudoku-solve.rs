@@ -1,8 +1,9 @@
-use sudoku_rs::{*, utils::Output};
+use sudoku_rs::{*, utils::{InputRecord, Output, OutputOptions, RenderOptions, candidates_allow, detect_puzzle_format, is_fpuzzles, parse_fpuzzles, parse_puzzle, read_records}};
 
 use structopt::StructOpt;
 use std::fs::File;
 use std::io::prelude::*;
+use std::time::Duration;
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "sudoku-solve")]
@@ -31,29 +32,91 @@ struct Options {
   #[structopt(long, name = "#SOLUTIONS")]
   num_solutions: Option<usize>,
 
-  /// Input json file name. If not specified, the input will come from command line
+  /// How the search picks the slot to branch on, one of `first`, `mrv`,
+  /// `mrv-degree`, and `unit`
+  #[structopt(long, default_value = "mrv", name = "SELECTION")]
+  slot_selection: SlotSelection,
+
+  /// How the search orders the possible answers of a slot, one of `natural`,
+  /// `random`, and `lcv`. With `random` and `--no-random`, the largest answer
+  /// is tried first
+  #[structopt(long, default_value = "random", name = "ORDERING")]
+  value_ordering: ValueOrdering,
+
+  /// Stop searching for solutions after the given number of seconds
+  #[structopt(long, name = "SECONDS")]
+  timeout: Option<f64>,
+
+  /// Print the statistics of the search (nodes, backtracks, time, ...) to stderr
+  #[structopt(long)]
+  stats: bool,
+
+  /// The solver backend, either `search` (backtracking search) or `sat` (the
+  /// embedded SAT solver, needs the `sat` feature)
+  #[structopt(long, default_value = "search", name = "BACKEND")]
+  backend: String,
+
+  /// Write the board as a CNF formula in DIMACS format instead of solving it
+  #[structopt(long)]
+  emit_cnf: bool,
+
+  /// With `--emit-cnf` or the `sat` backend, also require every digit to
+  /// appear once on both diagonals
+  #[structopt(long)]
+  cnf_diagonals: bool,
+
+  /// Input file name, either json or the puzzle file of a desktop sudoku tool
+  /// (SadMan Sudoku `.sdk`, Simple Sudoku `.ss`, or a HoDoKu pencil mark
+  /// grid), or an f-puzzles variant puzzle, the format being detected from the
+  /// content. If not specified, the input will come from command line
   #[structopt(short = "i", long, name = "INPUT")]
   input: Option<String>,
 
+  /// A variant puzzle of f-puzzles or SudokuPad: a link, its compressed
+  /// payload, or the json. Diagonals, killer cages, thermometers, arrows, and
+  /// irregular regions are solved for
+  #[structopt(long, name = "PUZZLE")]
+  fpuzzles: Option<String>,
+
+  /// Solve f-puzzles puzzles with unsupported rules anyway, leaving the
+  /// unsupported rules out
+  #[structopt(long)]
+  ignore_unsupported: bool,
+
+  /// Solve every record of the input file (json, ndjson, binary, or CSV with a
+  /// `question` column) instead of a single board, writing each question with
+  /// its first solution. Records without a solution are reported on stderr
+  #[structopt(long)]
+  batch: bool,
+
   /// Output json file name. If not specified, the output will be printed on screen
   #[structopt(short = "o", long, name = "OUTPUT")]
   output: Option<String>,
+
+  #[structopt(flatten)]
+  format: OutputOptions,
+
+  #[structopt(flatten)]
+  render: RenderOptions,
 }
 
-fn board_vec_from_file(input_file: &str) -> Result<Vec<u8>, String> {
+fn read_input_file(input_file: &str) -> Result<String, String> {
   let mut file = File::open(input_file).map_err(|_| "Cannot open input file")?;
   let mut contents = String::new();
   file.read_to_string(&mut contents).map_err(|_| "Cannot read from input file")?;
+  Ok(contents)
+}
 
+fn board_vec_from_json(contents: &str) -> Result<Vec<u8>, String> {
   // Parse the nested array [[x, x, ...], [x, x, ...]] format
-  let json : serde_json::Result<Vec<Vec<u8>>> = serde_json::from_str(&contents);
+  let json : serde_json::Result<Vec<Vec<u8>>> = serde_json::from_str(contents);
   if let Ok(json) = json {
     let board_vec = json.into_iter().flatten().collect::<Vec<_>>();
     return Ok(board_vec);
   }
 
   // Parse the array [x, x, ...] format
-  let json : serde_json::Result<Vec<u8>> = serde_json::from_str(&contents);
+  let json : serde_json::Result<Vec<u8>> = serde_json::from_str(contents);
   if let Ok(json) = json {
     return Ok(json);
   }
@@ -72,59 +135,220 @@ fn output_solution<B: Board>(output: &mut Output, i: usize, solution: B) -> Resu
   output.output_board(&solution)
 }
 
-fn execute_board<B: Board>(board: B, options: &Options) -> Result<(), String> {
-  let mut output = Output::new(&options.output)?;
-
-  // Initialize output
-  output.output_init()?;
+fn backend(options: &Options) -> Result<SolverBackend, String> {
+  match options.backend.as_str() {
+    "search" => Ok(SolverBackend::Search),
+    #[cfg(feature = "sat")]
+    "sat" => Ok(SolverBackend::Sat(CnfOptions { diagonals: options.cnf_diagonals })),
+    #[cfg(not(feature = "sat"))]
+    "sat" => Err("The sat backend needs sudoku-rs to be built with the `sat` feature".to_string()),
+    other => Err(format!("Unknown backend `{}`, expected search or sat", other)),
+  }
+}
 
-  let solution_iter = if options.no_random {
-    board.solve_with_ctx(SolvingContext::deterministic())
+/// The solving context constructed from options
+fn solving_context(options: &Options) -> Result<SolvingContext, String> {
+  let ctx = if options.no_random {
+    SolvingContext::deterministic()
   } else {
     match options.seed {
-      Some(seed) => board.solve_with_ctx(SolvingContext::random_with_seed(seed)),
-      _ => board.solve()
+      Some(seed) => SolvingContext::random_with_seed(seed),
+      _ => SolvingContext::random()
     }
   };
+  let ctx = ctx.with_heuristic(Strategy::new(options.slot_selection, options.value_ordering)).with_backend(backend(options)?);
+  let budget = match options.timeout {
+    Some(timeout) => SearchBudget::unlimited().with_timeout(Duration::from_secs_f64(timeout)),
+    None => SearchBudget::unlimited(),
+  };
+  Ok(ctx.with_budget(budget).with_stats(options.stats))
+}
 
-  // Generate solutions
-  match options.num_solutions {
-    Some(amount) => {
-      for (i, solution) in solution_iter.take(amount).enumerate() {
-        output_solution(&mut output, i, solution)?;
-      }
-    },
-    _ => {
-      for (i, solution) in solution_iter.enumerate() {
-        output_solution(&mut output, i, solution)?;
+fn execute_batch<B: Board>(records: &[InputRecord], options: &Options) -> Result<(), String> {
+  let mut output = options.format.output(&options.output)?.with_renderer(options.render.renderer());
+  output.output_init()?;
+
+  // Solve the records one by one, the timeout applying to each of them
+  let (mut num_solved, mut num_unsolved) = (0, 0);
+  for (i, record) in records.iter().enumerate() {
+    let question = B::from_u8_vec(&record.question);
+    let mut solutions = question.solve_with_ctx(solving_context(options)?);
+    let first = loop {
+      match solutions.try_next() {
+        Ok(Some(solution)) if !record.allows(&solution.to_u8_vec()) => continue,
+        result => break result,
       }
+    };
+    match first {
+      Ok(Some(solution)) => {
+        if num_solved > 0 {
+          output.output_separator()?;
+        }
+        output.output_board_with_solution(&question, &solution)?;
+        num_solved += 1;
+      },
+      Ok(None) => {
+        eprintln!("Record {} has no solution", i);
+        num_unsolved += 1;
+      },
+      Err(reason) => {
+        eprintln!("Record {} was not solved: {}", i, reason);
+        num_unsolved += 1;
+      },
     }
   }
+  output.output_finish()?;
+
+  if num_unsolved > 0 {
+    Err(format!("{} of the {} records were not solved", num_unsolved, records.len()))
+  } else {
+    Ok(())
+  }
+}
+
+/// Solve a board, only keeping the solutions allowed by the candidates stored
+/// in the input file, if any
+fn execute_board<B: Board>(board: B, candidates: Option<&[Vec<u8>]>, options: &Options) -> Result<(), String> {
+  let mut output = options.format.output(&options.output)?.with_renderer(options.render.renderer());
+
+  // Only write the CNF encoding of the board
+  if options.emit_cnf {
+    let mut cnf = board.to_cnf_with_options(&CnfOptions { diagonals: options.cnf_diagonals });
+    if let Some(candidates) = candidates {
+      cnf.add_candidates::<B>(candidates);
+    }
+    return output.write(&cnf.to_dimacs());
+  }
+
+  // Initialize output
+  output.output_init()?;
+
+  let mut solution_iter = board.solve_with_ctx(solving_context(options)?);
+  let allowed = |solution: &B| candidates.is_none_or(|candidates| candidates_allow(candidates, &solution.to_u8_vec()));
+
+  // Generate solutions
+  let mut num_solutions = 0;
+  for solution in solution_iter.by_ref().filter(allowed).take(options.num_solutions.unwrap_or(usize::MAX)) {
+    output_solution(&mut output, num_solutions, solution)?;
+    num_solutions += 1;
+  }
+
+  // Print the statistics of the search
+  if options.stats {
+    eprintln!("{}", solution_iter.stats());
+  }
 
   // Finish output
-  output.output_finish()
+  output.output_finish()?;
+
+  // Tell apart an exhausted budget from having found all the solutions
+  match solution_iter.status() {
+    SearchStatus::BudgetExhausted(reason) => Err(format!("Search stopped early: {}", reason)),
+    SearchStatus::Complete if solution_iter.stats().solutions == 0 => match board.explain_unsat() {
+      Some(explanation) => Err(format!("The board has no solution: {}", explanation)),
+      None => Err("The board has no solution".to_string()),
+    },
+    SearchStatus::Complete if num_solutions == 0 => Err("The candidates stored in the input file rule out every solution".to_string()),
+    _ => Ok(()),
+  }
+}
+
+fn execute_variant<B: Board>(puzzle: VariantPuzzle<B>, options: &Options) -> Result<(), String> {
+  let mut output = options.format.output(&options.output)?.with_renderer(options.render.renderer());
+
+  // Only write the CNF encoding of the puzzle, its constraints included
+  if options.emit_cnf {
+    let puzzle = CnfOptions { diagonals: options.cnf_diagonals }.constraints().into_iter().fold(puzzle, VariantPuzzle::constraint);
+    return output.write(&puzzle.to_cnf().to_dimacs());
+  }
+
+  output.output_init()?;
+
+  let mut solution_iter = puzzle.solve_with_ctx(solving_context(options)?);
+  let mut num_solutions = 0;
+  for solution in solution_iter.by_ref().take(options.num_solutions.unwrap_or(usize::MAX)) {
+    output_solution(&mut output, num_solutions, solution)?;
+    num_solutions += 1;
+  }
+
+  // Print the statistics of the search
+  if options.stats {
+    eprintln!("{}", solution_iter.stats());
+  }
+  output.output_finish()?;
+
+  match solution_iter.status() {
+    SearchStatus::BudgetExhausted(reason) => Err(format!("Search stopped early: {}", reason)),
+    SearchStatus::Complete if num_solutions == 0 => Err("The puzzle has no solution".to_string()),
+    _ => Ok(()),
+  }
+}
+
+fn execute_fpuzzles(contents: &str, options: &Options) -> Result<(), String> {
+  let puzzle = parse_fpuzzles(contents)?;
+  if !puzzle.unsupported.is_empty() {
+    let rules = puzzle.unsupported.join(", ");
+    if !options.ignore_unsupported {
+      return Err(format!("The puzzle has unsupported rules: {}. Use --ignore-unsupported to solve without them", rules));
+    }
+    eprintln!("Warning: leaving out the unsupported rules: {}", rules);
+  }
+  match puzzle.size {
+    4 => execute_variant(puzzle.to_variant::<Board4x4>()?, options),
+    9 => execute_variant(puzzle.to_variant::<Board9x9>()?, options),
+    size => Err(format!("Unsupported board size {}", size)),
+  }
 }
 
 fn main() -> Result<(), String> {
   let options = Options::from_args();
-  if let Some(input_file) = options.input.clone() {
-    let board_vec = board_vec_from_file(&input_file)?;
+  if options.batch {
+    let input_file = options.input.as_ref().ok_or("Batch mode needs an input file, given with --input")?;
+    let records = read_records(input_file)?;
+    for record in &records {
+      record.check()?;
+    }
+    match records.first().map(InputRecord::size) {
+      None => Err("Input file contains no record".to_string()),
+      Some(size) if records.iter().any(|record| record.size() != size) => {
+        Err("All the records in the input file must have the same board size".to_string())
+      },
+      Some(4) => execute_batch::<Board4x4>(&records, &options),
+      Some(9) => execute_batch::<Board9x9>(&records, &options),
+      Some(size) => Err(format!("Unsupported board size {}", size)),
+    }
+  } else if let Some(contents) = options.fpuzzles.clone() {
+    execute_fpuzzles(&contents, &options)
+  } else if let Some(input_file) = options.input.clone() {
+    let contents = read_input_file(&input_file)?;
+    if is_fpuzzles(&contents) {
+      return execute_fpuzzles(&contents, &options);
+    }
+
+    // Puzzle files of desktop tools may store candidates, which the solutions
+    // have to agree with
+    if detect_puzzle_format(&contents).is_some() {
+      let puzzle = parse_puzzle(&contents)?;
+      return execute_board(puzzle.board, puzzle.candidates.as_deref(), &options);
+    }
+
+    let board_vec = board_vec_from_json(&contents)?;
     if board_vec.len() == 16 {
       let board = Board4x4::from_u8_vec(&board_vec);
-      execute_board(board, &options)
+      execute_board(board, None, &options)
     } else if board_vec.len() == 81 {
       let board = Board9x9::from_u8_vec(&board_vec);
-      execute_board(board, &options)
+      execute_board(board, None, &options)
     } else {
       Err("Sudoku file must contain 16 or 81 elements".to_string())
     }
   } else if let Some(b4) = options.board4x4.clone() {
     let board = Board4x4::from_u8_vec(&b4);
-    execute_board(board, &options)
+    execute_board(board, None, &options)
   } else if let Some(b9) = options.board9x9.clone() {
     let board = Board9x9::from_u8_vec(&b9);
-    execute_board(board, &options)
+    execute_board(board, None, &options)
   } else {
-    Err("Must supply one of --input, --board4x4, or --board9x9".to_string())
+    Err("Must supply one of --input, --fpuzzles, --board4x4, or --board9x9".to_string())
   }
 }
\ No newline at end of file
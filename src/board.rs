@@ -1,12 +1,65 @@
-use rand::{SeedableRng, rngs::StdRng};
+use rand::{Rng, SeedableRng, rngs::StdRng};
 use rand::seq::SliceRandom;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::canonical;
+use crate::check::{self, EntryReport};
+use crate::cnf::*;
+use crate::difficulty::{self, Difficulty};
+use crate::explain::{self, Conflict, UnsatExplanation};
+use crate::heuristic::*;
+use crate::line::{digits_from_line, digits_to_line};
+use crate::rng::SolverRng;
+#[cfg(feature = "sat")]
+use crate::sat::{SatSolver, SatStats};
+use crate::trace::{self, SolveTrace};
+use crate::transform::Transform;
 
 /// Slot is a 2-tuple containing coordinate of the slot
 pub type Slot = (usize, usize);
 
+/// A unit is a row, a column, or a block, inside which every digit appears
+/// exactly once. Blocks are numbered in row major order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Unit {
+  Row(usize),
+  Column(usize),
+  Block(usize),
+}
+
+impl Unit {
+  /// Get all the slots inside the unit
+  pub fn slots<B: Board>(&self) -> Vec<Slot> {
+    match *self {
+      Unit::Row(i) => B::row_slots(i).collect(),
+      Unit::Column(j) => B::column_slots(j).collect(),
+      Unit::Block(b) => {
+        let block_size = B::block_size();
+        B::block_slots(&(b / block_size * block_size, b % block_size * block_size)).collect()
+      },
+    }
+  }
+}
+
+impl std::fmt::Display for Unit {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Unit::Row(i) => write!(f, "row {}", i),
+      Unit::Column(j) => write!(f, "column {}", j),
+      Unit::Block(b) => write!(f, "block {}", b),
+    }
+  }
+}
+
 /// A board element needs to specify an `is_unknown` function.
 /// This will be used when implementing the `Board` trait
-pub trait BoardElement : Copy + Clone + Default + std::fmt::Debug + ToString {
+///
+/// Elements are also convertible from and to `u8`, where `0` stands for unknown
+/// and `1` to `size` stand for the digits
+pub trait BoardElement : Copy + Clone + Default + Eq + std::hash::Hash + Ord + std::fmt::Debug + ToString + From<u8> + Into<u8> {
 
   /// Check if this board element is unknown
   fn is_unknown(&self) -> bool;
@@ -18,7 +71,7 @@ pub trait BoardElement : Copy + Clone + Default + std::fmt::Debug + ToString {
 }
 
 /// An ElementSet is a set of elements, usually implemented in bitset format.
-pub trait ElementSet : Default + Copy + Clone + std::fmt::Display {
+pub trait ElementSet : Default + Copy + Clone + Eq + std::hash::Hash + Ord + std::fmt::Display {
   type Element : BoardElement;
 
   /// Get the intersection between two element sets
@@ -38,7 +91,9 @@ pub trait ElementSet : Default + Copy + Clone + std::fmt::Display {
 }
 
 /// The trait for a Sudoku Board
-pub trait Board : Sized + Clone + std::fmt::Debug {
+///
+/// Boards are compared and ordered element by element in row major order
+pub trait Board : Sized + Clone + Default + Eq + std::hash::Hash + Ord + std::fmt::Debug {
 
   /// You need to specify the element that is being stored inside the board. It
   /// has to be a `BoardElement`
@@ -62,6 +117,62 @@ pub trait Board : Sized + Clone + std::fmt::Debug {
   /// You need to implement getting mutable element by slot
   fn get_mut(&mut self, slot: &Slot) -> &mut Self::Element;
 
+  /// Construct a board from its elements in row major order, where `0` stands
+  /// for unknown. Panics when `v` is not `size * size` elements long or holds
+  /// an invalid element, see `try_from_u8_vec`
+  fn from_u8_vec(v: &[u8]) -> Self {
+    assert_eq!(v.len(), Self::size() * Self::size(), "Invalid number of elements for a board of size {}", Self::size());
+    let mut board = Self::default();
+    for (slot, n) in Self::slots().zip(v) {
+      board.set(&slot, Self::Element::from(*n));
+    }
+    board
+  }
+
+  /// Construct a board from its elements in row major order, where `0` stands
+  /// for unknown, checking the number of elements and their range
+  ///
+  /// ```
+  /// # use sudoku_rs::*;
+  /// assert!(Board4x4::try_from_u8_vec(&[1, 0, 0, 0]).is_err());
+  /// assert!(Board4x4::try_from_u8_vec(&[7; 16]).is_err());
+  /// assert_eq!(Board4x4::try_from_u8_vec(&[0; 16]), Ok(Board4x4::default()));
+  /// ```
+  fn try_from_u8_vec(v: &[u8]) -> Result<Self, String> {
+    if v.len() != Self::size() * Self::size() {
+      return Err(format!("Board with {} elements is not a board of size {}", v.len(), Self::size()));
+    }
+    if let Some(n) = v.iter().find(|n| **n as usize > Self::size()) {
+      return Err(format!("Invalid element {} for a board of size {}", n, Self::size()));
+    }
+    Ok(Self::from_u8_vec(v))
+  }
+
+  /// Get the elements of the board in row major order, where `0` stands for
+  /// unknown
+  fn to_u8_vec(&self) -> Vec<u8> {
+    Self::slots().map(|slot| (*self.get(&slot)).into()).collect()
+  }
+
+  /// Parse a board from a single line of digits in row major order, where `.`
+  /// or `0` stands for unknown
+  ///
+  /// ```
+  /// # use sudoku_rs::*;
+  /// let board = Board4x4::from_line("1...3.......4..2").unwrap();
+  /// assert_eq!(board.to_u8_vec(), vec![1, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 2]);
+  /// assert_eq!(board.to_line(), "1...3.......4..2");
+  /// ```
+  fn from_line(line: &str) -> Result<Self, String> {
+    Self::try_from_u8_vec(&digits_from_line(line)?)
+  }
+
+  /// Write the board as a single line of digits in row major order, with `.`
+  /// for unknown
+  fn to_line(&self) -> String {
+    digits_to_line(&self.to_u8_vec())
+  }
+
   /// Setting an item at the given slot
   fn set(&mut self, slot: &Slot, item: Self::Element) {
     *self.get_mut(slot) = item;
@@ -88,6 +199,7 @@ pub trait Board : Sized + Clone + std::fmt::Debug {
   /// Get all the slots
   ///
   /// ```
+  /// # use sudoku_rs::*;
   /// for slot in Board4x4::slots() {
   ///   // ...
   /// }
@@ -98,7 +210,7 @@ pub trait Board : Sized + Clone + std::fmt::Debug {
 
   /// Get all the unknown slots (returns a vector of `Slot`)
   fn unknown_slots(&self) -> Vec<Slot> {
-    Self::slots().filter(|slot| self.get(&slot).is_unknown()).collect()
+    Self::slots().filter(|slot| self.get(slot).is_unknown()).collect()
   }
 
   /// Check if the board contains at least one unknown slot
@@ -112,6 +224,12 @@ pub trait Board : Sized + Clone + std::fmt::Debug {
     false
   }
 
+  /// Get all the units: rows first, then columns, then blocks
+  fn units() -> Vec<Unit> {
+    let size = Self::size();
+    (0..size).map(Unit::Row).chain((0..size).map(Unit::Column)).chain((0..size).map(Unit::Block)).collect()
+  }
+
   /// Get all the slots in the given row
   fn row_slots(row: usize) -> RowSlotIterator {
     RowSlotIterator { size: Self::size(), row, j: 0 }
@@ -211,7 +329,7 @@ pub trait Board : Sized + Clone + std::fmt::Debug {
 
   /// Put (`amount`) unknowns at random locations inside the board with a specified
   /// random generator
-  fn put_random_unknowns_with_rng(&mut self, amount: usize, rng: &mut StdRng) {
+  fn put_random_unknowns_with_rng<R: Rng + ?Sized>(&mut self, amount: usize, rng: &mut R) {
     let mut all_slots = Self::slots().collect::<Vec<_>>();
     all_slots.shuffle(rng);
     for slot in &all_slots[..amount] {
@@ -219,6 +337,129 @@ pub trait Board : Sized + Clone + std::fmt::Debug {
     }
   }
 
+  /// Apply a symmetry transformation to the board, returning the transformed board
+  fn transform(&self, transform: &Transform) -> Self {
+    transform.apply(self)
+  }
+
+  /// Get a random isomorph of the board, along with the transform being applied
+  fn random_isomorph_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> (Self, Transform) {
+    let transform = Transform::random::<Self, _>(rng);
+    (transform.apply(self), transform)
+  }
+
+  /// Get the lexicographically minimal board among all the boards equivalent
+  /// to this one under sudoku symmetries, along with the transform mapping this
+  /// board onto it
+  fn canonical_form(&self) -> (Self, Transform) {
+    canonical::canonical_form(self)
+  }
+
+  /// Check if the two boards are equivalent under sudoku symmetries
+  fn is_isomorphic(&self, other: &Self) -> bool {
+    self.canonical_form().0 == other.canonical_form().0
+  }
+
+  /// Encode the board as a boolean formula in conjunctive normal form, e.g. to
+  /// be solved by an external SAT solver after `Cnf::to_dimacs`
+  fn to_cnf(&self) -> Cnf {
+    self.to_cnf_with_options(&CnfOptions::default())
+  }
+
+  /// Encode the board as a boolean formula with the given options
+  fn to_cnf_with_options(&self, options: &CnfOptions) -> Cnf {
+    Cnf::encode(self, options)
+  }
+
+  /// Build the board from a model of its CNF encoding
+  fn from_model(model: &[i32]) -> Result<Self, String> {
+    Cnf::decode(model)
+  }
+
+  /// Find the pairs of known slots holding the same digit inside a unit
+  fn conflicts(&self) -> Vec<Conflict> {
+    explain::conflicts(self)
+  }
+
+  /// Check that no two known slots hold the same digit inside a unit
+  fn validate(&self) -> Result<(), Vec<Conflict>> {
+    let conflicts = self.conflicts();
+    if conflicts.is_empty() { Ok(()) } else { Err(conflicts) }
+  }
+
+  /// Explain why the board has no solution, either by the conflicts among its
+  /// known slots or by a minimal set of known slots that already has no
+  /// solution. Returns `None` when the board has a solution
+  fn explain_unsat(&self) -> Option<UnsatExplanation> {
+    explain::explain_unsat(self)
+  }
+
+  /// Check the entries of a player against this puzzle. `entries` holds the
+  /// givens of the puzzle along with the digits filled in by the player
+  ///
+  /// ```
+  /// # use sudoku_rs::*;
+  /// let puzzle = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]);
+  /// let solution = puzzle.solve().next().unwrap();
+  /// let mut entries = puzzle.clone();
+  /// entries.set(&(0, 1), *solution.get(&(0, 1)));
+  /// let report = puzzle.check_entries(&entries).unwrap();
+  /// assert_eq!(report.entries, vec![(0, 1)]);
+  /// assert!(report.wrong.is_empty());
+  /// ```
+  fn check_entries(&self, entries: &Self) -> Result<EntryReport, String> {
+    check::check_entries(self, entries)
+  }
+
+  /// Rate how hard the board is to solve for a human: `Easy` when filling in
+  /// naked singles solves it, `Medium` when hidden singles are also needed,
+  /// and `Hard` or `Expert` when guessing is needed, depending on how many
+  /// guesses the search makes before finding a solution
+  ///
+  /// ```
+  /// # use sudoku_rs::*;
+  /// let board = Board4x4::from_u8_vec(&[1, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 0]);
+  /// assert_eq!(board.difficulty(), Difficulty::Easy);
+  /// ```
+  fn difficulty(&self) -> Difficulty {
+    difficulty::rate(self)
+  }
+
+  /// Solve the board step by step, recording every placement with its reason:
+  /// the givens, then naked and hidden singles placed one at a time, guesses,
+  /// and backtracks. Slots and digits are picked in the order of
+  /// `SolvingContext::deterministic`, but since singles are placed one at a
+  /// time, a board with several solutions may end at a different one. Use
+  /// `solve_with_trace_to` to end at a given solution
+  ///
+  /// ```
+  /// # use sudoku_rs::*;
+  /// let board = Board4x4::from_u8_vec(&[1, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 0]);
+  /// let trace = board.solve_with_trace();
+  /// assert_eq!(trace.steps.len(), 16);
+  /// assert_eq!(trace.steps[15], TraceStep { slot: (3, 3), value: 1, reason: StepReason::NakedSingle });
+  /// assert_eq!(trace.boards().last(), trace.solution.as_ref());
+  /// ```
+  fn solve_with_trace(&self) -> SolveTrace<Self> {
+    trace::trace(self, None)
+  }
+
+  /// Like `solve_with_trace`, but ending at `target`, which should be a
+  /// solution of the board: guesses leading to another solution are taken back
+  /// like contradictions. The trace of a board with a unique solution is the
+  /// same as the one of `solve_with_trace`
+  ///
+  /// ```
+  /// # use sudoku_rs::*;
+  /// let target = Board4x4::from_u8_vec(&[4, 3, 2, 1, 2, 1, 4, 3, 3, 4, 1, 2, 1, 2, 3, 4]);
+  /// let trace = Board4x4::default().solve_with_trace_to(&target);
+  /// assert_eq!(trace.solution.as_ref(), Some(&target));
+  /// assert_eq!(trace.boards().last(), Some(&target));
+  /// ```
+  fn solve_with_trace_to(&self, target: &Self) -> SolveTrace<Self> {
+    trace::trace(self, Some(target))
+  }
+
   /// Find the slots that only contain one possible answer, and fill that answer
   /// in.
   ///
@@ -248,15 +489,99 @@ pub trait Board : Sized + Clone + std::fmt::Debug {
     else { FillResult::Unmodified }
   }
 
+  /// Find the digits that only have one possible slot inside a row, a column,
+  /// or a block (hidden singles), and fill them in.
+  ///
+  /// The results are the same as `put_determined_answers`, except that the
+  /// result is "Unsatisfied" when a digit missing from a unit has no possible
+  /// slot inside it
+  fn put_hidden_singles(&mut self) -> FillResult {
+    let mut modified = false;
+    for unit in Self::units().iter().map(Unit::slots::<Self>) {
+      // Look at the unit again after every placement, as possible answers change
+      'unit: loop {
+        let mut present = Self::ElementSet::default();
+        for slot in &unit {
+          present.insert(self.get(slot));
+        }
+        let candidates = unit.iter()
+          .filter(|slot| self.get(slot).is_unknown())
+          .map(|slot| (*slot, self.possible_answers(slot)))
+          .collect::<Vec<_>>();
+        for digit in present.complement().elements() {
+          let mut digit_set = Self::ElementSet::default();
+          digit_set.insert(&digit);
+          let mut places = candidates.iter().filter(|(_, answers)| answers.intersect(&digit_set).count() > 0);
+          match (places.next(), places.next()) {
+            (None, _) => return FillResult::Unsatisfied,
+            (Some((slot, _)), None) => {
+              self.set(slot, digit);
+              modified = true;
+              continue 'unit;
+            },
+            _ => {}
+          }
+        }
+        break;
+      }
+    }
+    if modified { FillResult::Modified }
+    else { FillResult::Unmodified }
+  }
+
+  /// Fill in naked singles (`put_determined_answers`) and hidden singles
+  /// (`put_hidden_singles`) until there is nothing left to fill in, or until
+  /// the board turns out to be unsatisfiable.
+  ///
+  /// ```
+  /// # use sudoku_rs::*;
+  /// // The top left slot is the only place left for a 1 in the first row
+  /// let mut board = Board4x4::from_u8_vec(&[0, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+  /// assert_eq!(board.propagate(), FillResult::Modified);
+  /// assert_eq!(board.to_u8_vec()[0], 1);
+  /// ```
+  fn propagate(&mut self) -> FillResult {
+    let mut modified = false;
+    loop {
+      let naked = self.put_determined_answers();
+      if naked == FillResult::Unsatisfied {
+        return FillResult::Unsatisfied;
+      }
+      let hidden = self.put_hidden_singles();
+      if hidden == FillResult::Unsatisfied {
+        return FillResult::Unsatisfied;
+      }
+      if naked == FillResult::Unmodified && hidden == FillResult::Unmodified {
+        break;
+      }
+      modified = true;
+    }
+    if modified { FillResult::Modified }
+    else { FillResult::Unmodified }
+  }
+
   /// Solve with a solving context
+  ///
+  /// A board whose known slots already conflict has no solution
   fn solve_with_ctx(&self, ctx: SolvingContext) -> BoardSolutions<Self> {
-    BoardSolutions { stack: vec![self.clone()], ctx }
+    let stack = if self.conflicts().is_empty() { vec![self.clone()] } else { vec![] };
+    BoardSolutions {
+      stack,
+      ctx,
+      stats: SolveStats::default(),
+      status: SearchStatus::InProgress,
+      blocked: vec![],
+      #[cfg(feature = "sat")]
+      sat: None,
+    }
   }
 
   /// Solve the board by returning a solutions iterator.
   ///
   /// ```
-  /// for solution in board.solve(true) {
+  /// # use sudoku_rs::*;
+  /// let board = Board4x4::default();
+  /// for solution in board.solve() {
   ///   // ...
   /// }
   /// ```
@@ -265,49 +590,241 @@ pub trait Board : Sized + Clone + std::fmt::Debug {
   }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FillResult {
   Modified,
   Unmodified,
   Unsatisfied,
 }
 
-pub enum SolvingContext {
-  Deterministic,
-  Random(StdRng),
+/// Limits on the amount of work done by a search. The search stops with a
+/// `BudgetExhausted` outcome as soon as one of the limits is reached
+#[derive(Clone, Debug, Default)]
+pub struct SearchBudget {
+  /// Maximum number of nodes (boards taken from the search stack)
+  pub max_nodes: Option<usize>,
+
+  /// Wall-clock time after which the search stops
+  pub deadline: Option<Instant>,
+
+  /// Flag that can be raised from another thread to stop the search
+  pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl SearchBudget {
+  /// A budget with no limit at all
+  pub fn unlimited() -> Self {
+    Self::default()
+  }
+
+  /// Limit the number of nodes being expanded
+  pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+    self.max_nodes = Some(max_nodes);
+    self
+  }
+
+  /// Stop the search at the given instant
+  pub fn with_deadline(mut self, deadline: Instant) -> Self {
+    self.deadline = Some(deadline);
+    self
+  }
+
+  /// Stop the search after `timeout` from now
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    self.with_deadline(Instant::now() + timeout)
+  }
+
+  /// Stop the search once `cancel` is set to `true`
+  pub fn with_cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+    self.cancel = Some(cancel);
+    self
+  }
+
+  /// Check whether the budget allows expanding one more node, given the
+  /// number of nodes expanded so far
+  pub fn check(&self, nodes: usize) -> Result<(), BudgetExhausted> {
+    if self.max_nodes.is_some_and(|max_nodes| nodes >= max_nodes) {
+      Err(BudgetExhausted::Nodes)
+    } else if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+      Err(BudgetExhausted::Deadline)
+    } else if self.cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+      Err(BudgetExhausted::Cancelled)
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/// The reason why a search stopped before exploring the whole search space
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BudgetExhausted {
+  Nodes,
+  Deadline,
+  Cancelled,
+}
+
+impl std::fmt::Display for BudgetExhausted {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    f.write_str(match self {
+      Self::Nodes => "node limit reached",
+      Self::Deadline => "time limit reached",
+      Self::Cancelled => "search cancelled",
+    })
+  }
+}
+
+/// The state of a search
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchStatus {
+  /// There might be more solutions to find
+  InProgress,
+
+  /// The whole search space has been explored, there is no more solution
+  Complete,
+
+  /// The search stopped because its budget is exhausted. There might still be
+  /// more solutions
+  BudgetExhausted(BudgetExhausted),
+}
+
+/// The algorithm used to find the solutions of a board
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SolverBackend {
+  /// Backtracking search with propagation, guided by the heuristic
+  #[default]
+  Search,
+
+  /// Encode the board as clauses (with the given options) and solve them with
+  /// the embedded CDCL solver. Solutions are enumerated by blocking every
+  /// solution found
+  #[cfg(feature = "sat")]
+  Sat(CnfOptions),
+}
+
+/// The settings of a search: the random generator (if any), the budget, the
+/// branching heuristic, the backend, and whether detailed statistics are
+/// collected
+pub struct SolvingContext {
+  pub(crate) rng: Option<SolverRng>,
+  pub(crate) budget: SearchBudget,
+  pub(crate) heuristic: Box<dyn Heuristic>,
+  pub(crate) backend: SolverBackend,
+  pub(crate) collect_stats: bool,
 }
 
 impl Default for SolvingContext {
   fn default() -> Self {
-    Self::Random(StdRng::from_entropy())
+    Self::random()
   }
 }
 
 impl SolvingContext {
+  /// A search without randomness, branching on the slot with the least
+  /// possible answers and trying the largest one first
   pub fn deterministic() -> Self {
-    Self::Deterministic
+    Self::with_rng(None)
   }
 
+  /// A random search, branching on the slot with the least possible answers
+  /// and trying them in random order
   pub fn random() -> Self {
-    Self::Random(StdRng::from_entropy())
+    Self::with_rng(Some(SolverRng::from_entropy()))
   }
 
   pub fn random_with_seed(seed: u64) -> Self {
-    Self::Random(StdRng::seed_from_u64(seed))
+    Self::with_rng(Some(SolverRng::seed_from_u64(seed)))
+  }
+
+  fn with_rng(rng: Option<SolverRng>) -> Self {
+    let heuristic = Box::new(Strategy::new(SlotSelection::MinimumRemainingValues, ValueOrdering::Random));
+    Self { rng, budget: SearchBudget::default(), heuristic, backend: SolverBackend::default(), collect_stats: false }
+  }
+
+  /// Also collect the statistics that cost some work on every node (forced
+  /// placements and elapsed time), see `SolveStats`
+  pub fn with_stats(mut self, collect_stats: bool) -> Self {
+    self.collect_stats = collect_stats;
+    self
+  }
+
+  /// Use another solver backend
+  pub fn with_backend(mut self, backend: SolverBackend) -> Self {
+    self.backend = backend;
+    self
+  }
+
+  pub fn backend(&self) -> SolverBackend {
+    self.backend
+  }
+
+  /// Use another branching heuristic
+  pub fn with_heuristic<H: Heuristic + 'static>(mut self, heuristic: H) -> Self {
+    self.heuristic = Box::new(heuristic);
+    self
+  }
+
+  /// Limit the amount of work done by the search
+  pub fn with_budget(mut self, budget: SearchBudget) -> Self {
+    self.budget = budget;
+    self
   }
 
   pub fn is_random(&self) -> bool {
-    match self {
-      Self::Deterministic => false,
-      Self::Random(_) => true,
-    }
+    self.rng.is_some()
   }
 
-  pub fn rng(&mut self) -> Option<&mut StdRng> {
-    match self {
-      Self::Deterministic => None,
-      Self::Random(rng) => Some(rng)
-    }
+  pub fn rng(&mut self) -> Option<&mut SolverRng> {
+    self.rng.as_mut()
+  }
+
+  pub fn budget(&self) -> &SearchBudget {
+    &self.budget
+  }
+
+  pub fn budget_mut(&mut self) -> &mut SearchBudget {
+    &mut self.budget
+  }
+}
+
+/// Statistics of the search done by a `BoardSolutions` iterator, accumulated
+/// over all the calls to `next`. The counters of nodes, backtracks, guesses,
+/// stack depth and solutions are always kept, while forced placements and
+/// elapsed time are only collected when `SolvingContext::with_stats` asks for
+/// them
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolveStats {
+  /// Number of boards taken from the search stack
+  pub nodes: usize,
+
+  /// Number of boards abandoned because one of their slots has no possible answer
+  pub backtracks: usize,
+
+  /// Number of answers filled in by propagation
+  pub forced_placements: usize,
+
+  /// Number of branches taken by the search, each of them guessing the answer
+  /// of a slot, including the ones abandoned later on
+  pub guesses: usize,
+
+  /// Maximum size of the search stack
+  pub max_stack_depth: usize,
+
+  /// Number of solutions found
+  pub solutions: usize,
+
+  /// Time spent searching
+  pub elapsed: Duration,
+}
+
+impl std::fmt::Display for SolveStats {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    writeln!(f, "Solutions:         {}", self.solutions)?;
+    writeln!(f, "Nodes expanded:    {}", self.nodes)?;
+    writeln!(f, "Backtracks:        {}", self.backtracks)?;
+    writeln!(f, "Forced placements: {}", self.forced_placements)?;
+    writeln!(f, "Guesses:           {}", self.guesses)?;
+    writeln!(f, "Max stack depth:   {}", self.max_stack_depth)?;
+    write!(f, "Elapsed time:      {:?}", self.elapsed)
   }
 }
 
@@ -315,65 +832,231 @@ impl SolvingContext {
 pub struct BoardSolutions<B> where B : Board {
   stack: Vec<B>,
   ctx: SolvingContext,
+  stats: SolveStats,
+  status: SearchStatus,
+  /// The solutions found by the SAT backend, which are blocked from now on
+  blocked: Vec<B>,
+  #[cfg(feature = "sat")]
+  sat: Option<SatSolver>,
 }
 
-impl<B> Iterator for BoardSolutions<B> where B : Board {
-  type Item = B;
+impl<B> BoardSolutions<B> where B : Board {
+  /// Get the statistics of the search done so far
+  pub fn stats(&self) -> &SolveStats {
+    &self.stats
+  }
+
+  /// Get the state of the search. When the iterator returns `None`, this tells
+  /// whether the search is complete or ran out of budget
+  pub fn status(&self) -> SearchStatus {
+    self.status
+  }
+
+  /// Get the solving context, e.g. to extend the budget of a search
+  pub fn ctx_mut(&mut self) -> &mut SolvingContext {
+    &mut self.ctx
+  }
+
+  /// Save the state of the search into json, so that it can be resumed later
+  /// with `from_checkpoint`. The state contains the search stack, the state
+  /// of the random generator, and the statistics
+  pub fn checkpoint(&self) -> Value {
+    let stats = &self.stats;
+    json!({
+      "stack": self.stack.iter().map(Board::to_u8_vec).collect::<Vec<_>>(),
+      "blocked": self.blocked.iter().map(Board::to_u8_vec).collect::<Vec<_>>(),
+      "rng": self.ctx.rng.as_ref().map(SolverRng::to_json),
+      "stats": {
+        "nodes": stats.nodes,
+        "backtracks": stats.backtracks,
+        "forced_placements": stats.forced_placements,
+        "guesses": stats.guesses,
+        "max_stack_depth": stats.max_stack_depth,
+        "solutions": stats.solutions,
+        "elapsed": stats.elapsed.as_secs_f64(),
+      },
+    })
+  }
+
+  /// Resume a search saved with `checkpoint`. The random generator of `ctx` is
+  /// replaced by the one saved in the checkpoint, so that the search continues
+  /// exactly where it left off as long as `ctx` uses the same heuristic
+  pub fn from_checkpoint(checkpoint: &Value, mut ctx: SolvingContext) -> Result<Self, String> {
+    let parse_boards = |boards: &[Value]| boards.iter().map(|board| {
+      let board = board.as_array().filter(|board| board.len() == B::size() * B::size()).ok_or("Invalid board in checkpoint")?;
+      let elements = board.iter()
+        .map(|n| n.as_u64().filter(|n| *n as usize <= B::size()).map(|n| n as u8))
+        .collect::<Option<Vec<_>>>().ok_or("Invalid board element in checkpoint")?;
+      Ok(B::from_u8_vec(&elements))
+    }).collect::<Result<Vec<_>, String>>();
+    let stack = parse_boards(checkpoint["stack"].as_array().ok_or("Missing search stack in checkpoint")?)?;
+    let blocked = parse_boards(checkpoint["blocked"].as_array().map_or(&[], Vec::as_slice))?;
+    ctx.rng = match &checkpoint["rng"] {
+      Value::Null => None,
+      rng => Some(SolverRng::from_json(rng)?),
+    };
+    let saved = &checkpoint["stats"];
+    let count = |name: &str| saved[name].as_u64().unwrap_or(0) as usize;
+    let stats = SolveStats {
+      nodes: count("nodes"),
+      backtracks: count("backtracks"),
+      forced_placements: count("forced_placements"),
+      guesses: count("guesses"),
+      max_stack_depth: count("max_stack_depth"),
+      solutions: count("solutions"),
+      elapsed: Duration::from_secs_f64(saved["elapsed"].as_f64().unwrap_or(0.0)),
+    };
+    let status = if stack.is_empty() { SearchStatus::Complete } else { SearchStatus::InProgress };
+    Ok(Self {
+      stack,
+      ctx,
+      stats,
+      status,
+      blocked,
+      #[cfg(feature = "sat")]
+      sat: None,
+    })
+  }
+
+  /// Get the next solution. Returns `Ok(None)` when there is no more solution,
+  /// and `Err` when the budget is exhausted before finding the next solution.
+  /// The search can continue after its budget is extended
+  pub fn try_next(&mut self) -> Result<Option<B>, BudgetExhausted> {
+    let start = self.ctx.collect_stats.then(Instant::now);
+    let result = self.next_solution();
+    if let Some(start) = start {
+      self.stats.elapsed += start.elapsed();
+    }
+    self.status = match result {
+      Ok(Some(_)) => SearchStatus::InProgress,
+      Ok(None) => SearchStatus::Complete,
+      Err(reason) => SearchStatus::BudgetExhausted(reason),
+    };
+    result
+  }
+
+  fn next_solution(&mut self) -> Result<Option<B>, BudgetExhausted> {
+    #[cfg(feature = "sat")]
+    if let SolverBackend::Sat(options) = self.ctx.backend {
+      return self.next_sat_solution(&options);
+    }
 
-  fn next(&mut self) -> Option<Self::Item> {
     while !self.stack.is_empty() {
-      // Since stack is not empty there must be a stack top
+      self.ctx.budget.check(self.stats.nodes)?;
       let mut board = self.stack.pop().unwrap();
 
-      // Fill in determined answers
-      let mut fill_res = FillResult::Modified;
-      if fill_res == FillResult::Modified {
-        fill_res = board.put_determined_answers();
+      // Every board but the first one is a branch taken on a guess
+      if self.stats.nodes > 0 {
+        self.stats.guesses += 1;
+      }
+      self.stats.nodes += 1;
+
+      // Fill in forced answers until nothing changes
+      let num_unknowns = if self.ctx.collect_stats { board.unknown_slots().len() } else { 0 };
+      let fill_res = board.propagate();
+      if self.ctx.collect_stats {
+        self.stats.forced_placements += num_unknowns - board.unknown_slots().len();
       }
 
       // Check if unsatisfied
       if fill_res == FillResult::Unsatisfied {
+        self.stats.backtracks += 1;
         continue;
       }
 
       // If still satisfied, check empty slots
       if board.has_unknown_slot() {
 
-        // Cache the least constraint element
-        let mut least_constrained : Option<(usize, Slot, B::ElementSet)> = None;
+        // Pick the slot to branch on and order its possible answers
+        let node = BoardNode(&board);
+        let heuristic = &self.ctx.heuristic;
+        if let Some(slot) = heuristic.select_slot(&node, self.ctx.rng.as_mut()) {
+          let mut pos_answers = node.candidates(&slot);
+          heuristic.order_values(&node, &slot, &mut pos_answers, self.ctx.rng.as_mut());
 
-        // Get all the empty slots
-        let mut empty_slots = board.unknown_slots();
-        if let Some(rng) = self.ctx.rng() { empty_slots.shuffle(rng) }
-
-        // Iterate all slots to find least constraint one
-        for slot in empty_slots {
-          let pos_answers = board.possible_answers(&slot);
-          if least_constrained.is_none() || pos_answers.count() < least_constrained.unwrap().0 {
-            least_constrained = Some((pos_answers.count(), slot, pos_answers));
+          // A slot without any possible answer is a dead end
+          if pos_answers.is_empty() {
+            self.stats.backtracks += 1;
           }
-        }
-
-        // Put all modified boards onto the stack
-        if let Some((_, slot, pos_answers)) = least_constrained {
 
-          // Get all the possible answers
-          let mut pos_answers = pos_answers.elements();
-          if let Some(rng) = self.ctx.rng() { pos_answers.shuffle(rng) }
-
-          // Add all mutated boards onto the stack
-          for pos_answer in pos_answers {
+          // Add all mutated boards onto the stack, the first answer being on top
+          for pos_answer in pos_answers.into_iter().rev() {
             let mut new_board = board.clone();
-            new_board.set(&slot, pos_answer);
+            new_board.set(&slot, B::Element::from(pos_answer));
             self.stack.push(new_board);
           }
+          self.stats.max_stack_depth = self.stats.max_stack_depth.max(self.stack.len());
         }
 
       } else {
-        return Some(board)
+        self.stats.solutions += 1;
+        return Ok(Some(board))
       }
     }
-    None
+    Ok(None)
+  }
+}
+
+#[cfg(feature = "sat")]
+impl<B> BoardSolutions<B> where B : Board {
+  /// Get the next solution from the SAT solver. The board stays on the stack
+  /// until the search is complete, so that a checkpoint can rebuild the solver
+  fn next_sat_solution(&mut self, options: &CnfOptions) -> Result<Option<B>, BudgetExhausted> {
+    let board = match self.stack.first() {
+      Some(board) => board,
+      None => return Ok(None),
+    };
+    let (solver, before) = match &mut self.sat {
+      Some(solver) => {
+        let before = solver.stats();
+        (solver, before)
+      },
+      None => {
+        let mut solver = SatSolver::from_cnf(&board.to_cnf_with_options(options));
+        for solution in &self.blocked {
+          solver.add_clause(&Cnf::blocking_clause(solution));
+        }
+        if let Some(rng) = self.ctx.rng.as_mut() {
+          solver.randomize(rng);
+        }
+        (self.sat.insert(solver), SatStats::default())
+      }
+    };
+
+    // Decisions of the SAT solver count as nodes of the search, and the work
+    // done while loading the clauses counts as well
+    let (budget, nodes) = (&self.ctx.budget, self.stats.nodes);
+    let result = solver.solve_with_budget(|decisions| budget.check(nodes + decisions - before.decisions));
+    let after = solver.stats();
+    self.stats.nodes += after.decisions - before.decisions;
+    self.stats.guesses += after.decisions - before.decisions;
+    self.stats.backtracks += after.conflicts - before.conflicts;
+    self.stats.forced_placements += after.propagations - before.propagations;
+    self.stats.max_stack_depth = self.stats.max_stack_depth.max(after.max_level);
+
+    match result? {
+      Some(model) => {
+        let solution = B::from_model(&model).expect("Models of the encoding are complete boards");
+        solver.add_clause(&Cnf::blocking_clause(&solution));
+        self.blocked.push(solution.clone());
+        self.stats.solutions += 1;
+        Ok(Some(solution))
+      },
+      None => {
+        self.stack.clear();
+        Ok(None)
+      }
+    }
+  }
+}
+
+impl<B> Iterator for BoardSolutions<B> where B : Board {
+  type Item = B;
+
+  /// Returns `None` both when the search is complete and when its budget is
+  /// exhausted, use `status` or `try_next` to tell them apart
+  fn next(&mut self) -> Option<Self::Item> {
+    self.try_next().ok().flatten()
   }
 }
 
@@ -520,7 +1203,7 @@ impl Iterator for DiagonalSlotIterator {
       }
       Self::Major { size, i } => {
         if i < size {
-          let result = Some((i.clone(), i.clone()));
+          let result = Some((*i, *i));
           *i += 1;
           result
         } else {
@@ -529,7 +1212,7 @@ impl Iterator for DiagonalSlotIterator {
       },
       Self::Minor { size, i } => {
         if i < size {
-          let result = Some((i.clone(), size.clone() - i.clone() - 1));
+          let result = Some((*i, *size - *i - 1));
           *i += 1;
           result
         } else {
@@ -1,7 +1,7 @@
 use crate::board::*;
 
-#[derive(Copy, Clone)]
-pub enum Board4x4Element { U0, U1, U2, U3, U4 }
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Board4x4Element { #[default] U0, U1, U2, U3, U4 }
 
 impl From<u8> for Board4x4Element {
   fn from(n: u8) -> Self {
@@ -16,15 +16,21 @@ impl From<u8> for Board4x4Element {
   }
 }
 
-impl ToString for Board4x4Element {
-  fn to_string(&self) -> String {
-    match self {
+impl From<Board4x4Element> for u8 {
+  fn from(elem: Board4x4Element) -> Self {
+    elem as u8
+  }
+}
+
+impl std::fmt::Display for Board4x4Element {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fmt.write_str(match self {
       Self::U0 => "0",
       Self::U1 => "1",
       Self::U2 => "2",
       Self::U3 => "3",
       Self::U4 => "4",
-    }.to_string()
+    })
   }
 }
 
@@ -34,22 +40,13 @@ impl std::fmt::Debug for Board4x4Element {
   }
 }
 
-impl Default for Board4x4Element {
-  fn default() -> Self {
-    Self::U0
-  }
-}
-
 impl BoardElement for Board4x4Element {
   fn is_unknown(&self) -> bool {
-    match self {
-      Self::U0 => true,
-      _ => false
-    }
+    matches!(self, Self::U0)
   }
 }
 
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Board4x4ElementSet(u8);
 
 impl std::fmt::Display for Board4x4ElementSet {
@@ -66,7 +63,7 @@ impl ElementSet for Board4x4ElementSet {
   }
 
   fn complement(&self) -> Self {
-    Self(!self.0)
+    Self(!self.0 & 0b1111)
   }
 
   fn count(&self) -> usize {
@@ -97,7 +94,7 @@ impl ElementSet for Board4x4ElementSet {
   }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Board4x4 {
   board: [Board4x4Element; 16],
 }
@@ -107,18 +104,9 @@ impl Board4x4 {
     Self { board }
   }
 
-  pub fn from_u8_vec(v: &Vec<u8>) -> Self {
-    let mut board = [Board4x4Element::default(); 16];
-    for i in 0..16 {
-      board[i] = Board4x4Element::from(v[i]);
-    }
-    Self { board }
-  }
-}
-
-impl Default for Board4x4 {
-  fn default() -> Self {
-    Self { board: [Board4x4Element::default(); 16] }
+  /// Same as `Board::from_u8_vec`, without having to import the trait
+  pub fn from_u8_vec(v: &[u8]) -> Self {
+    <Self as Board>::from_u8_vec(v)
   }
 }
 
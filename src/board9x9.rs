@@ -1,7 +1,7 @@
 use crate::board::*;
 
-#[derive(Copy, Clone)]
-pub enum Board9x9Element { U0, U1, U2, U3, U4, U5, U6, U7, U8, U9 }
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Board9x9Element { #[default] U0, U1, U2, U3, U4, U5, U6, U7, U8, U9 }
 
 impl From<u8> for Board9x9Element {
   fn from(n: u8) -> Self {
@@ -21,9 +21,15 @@ impl From<u8> for Board9x9Element {
   }
 }
 
-impl ToString for Board9x9Element {
-  fn to_string(&self) -> String {
-    match self {
+impl From<Board9x9Element> for u8 {
+  fn from(elem: Board9x9Element) -> Self {
+    elem as u8
+  }
+}
+
+impl std::fmt::Display for Board9x9Element {
+  fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fmt.write_str(match self {
       Self::U0 => "0",
       Self::U1 => "1",
       Self::U2 => "2",
@@ -34,7 +40,7 @@ impl ToString for Board9x9Element {
       Self::U7 => "7",
       Self::U8 => "8",
       Self::U9 => "9",
-    }.to_string()
+    })
   }
 }
 
@@ -44,22 +50,13 @@ impl std::fmt::Debug for Board9x9Element {
   }
 }
 
-impl Default for Board9x9Element {
-  fn default() -> Self {
-    Self::U0
-  }
-}
-
 impl BoardElement for Board9x9Element {
   fn is_unknown(&self) -> bool {
-    match self {
-      Self::U0 => true,
-      _ => false
-    }
+    matches!(self, Self::U0)
   }
 }
 
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Board9x9ElementSet(u16);
 
 impl std::fmt::Display for Board9x9ElementSet {
@@ -76,7 +73,7 @@ impl ElementSet for Board9x9ElementSet {
   }
 
   fn complement(&self) -> Self {
-    Self(!self.0)
+    Self(!self.0 & 0b1_1111_1111)
   }
 
   fn count(&self) -> usize {
@@ -117,7 +114,7 @@ impl ElementSet for Board9x9ElementSet {
   }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Board9x9 {
   board: [Board9x9Element; 81],
 }
@@ -127,12 +124,9 @@ impl Board9x9 {
     Self { board }
   }
 
-  pub fn from_u8_vec(v: &Vec<u8>) -> Self {
-    let mut board = [Board9x9Element::default(); 81];
-    for i in 0..81 {
-      board[i] = Board9x9Element::from(v[i]);
-    }
-    Self { board }
+  /// Same as `Board::from_u8_vec`, without having to import the trait
+  pub fn from_u8_vec(v: &[u8]) -> Self {
+    <Self as Board>::from_u8_vec(v)
   }
 }
 
@@ -0,0 +1,220 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+use crate::board::*;
+
+/// A4 page size, in points
+const PAGE_WIDTH : f32 = 595.0;
+const PAGE_HEIGHT : f32 = 842.0;
+const MARGIN : f32 = 48.0;
+
+/// Room taken by the page header and footer
+const HEADER_HEIGHT : f32 = 36.0;
+const FOOTER_HEIGHT : f32 = 24.0;
+
+/// Room between a grid and its label
+const LABEL_HEIGHT : f32 = 20.0;
+
+const REGULAR_FONT : Name = Name(b"F1");
+const BOLD_FONT : Name = Name(b"F2");
+
+/// Lays out puzzles in a printable PDF booklet: a number of puzzles per page,
+/// each labeled with its number and difficulty, followed by the answers.
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let puzzle = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]);
+/// let solution = puzzle.solve().next().unwrap();
+/// let pdf = PuzzleBook::new("My puzzles").render(&[(puzzle, solution)]);
+/// assert!(pdf.starts_with(b"%PDF"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PuzzleBook {
+  /// Shown at the top of every page
+  pub title: String,
+
+  /// Number of puzzles laid out on a page
+  pub puzzles_per_page: usize,
+
+  /// Number of answers laid out on a page of the answer section
+  pub answers_per_page: usize,
+
+  /// Whether to append the answer section
+  pub answers: bool,
+}
+
+impl PuzzleBook {
+  pub fn new(title: &str) -> Self {
+    Self { title: title.to_string(), puzzles_per_page: 4, answers_per_page: 6, answers: true }
+  }
+
+  pub fn puzzles_per_page(mut self, puzzles_per_page: usize) -> Self {
+    self.puzzles_per_page = puzzles_per_page.max(1);
+    self
+  }
+
+  pub fn answers_per_page(mut self, answers_per_page: usize) -> Self {
+    self.answers_per_page = answers_per_page.max(1);
+    self
+  }
+
+  pub fn answers(mut self, answers: bool) -> Self {
+    self.answers = answers;
+    self
+  }
+
+  /// Write the book of the given puzzles, each with its solution, as a PDF
+  /// document
+  pub fn render<B: Board>(&self, puzzles: &[(B, B)]) -> Vec<u8> {
+    let mut pages = vec![];
+    for (k, chunk) in puzzles.chunks(self.puzzles_per_page).enumerate() {
+      let grids = chunk.iter().enumerate().map(|(i, (puzzle, _))| {
+        let n = k * self.puzzles_per_page + i + 1;
+        let label = format!("Puzzle {} - {}", n, capitalize(&puzzle.difficulty().to_string()));
+        (label, puzzle, puzzle)
+      }).collect::<Vec<_>>();
+      pages.push((self.title.clone(), grids, self.puzzles_per_page));
+    }
+    if self.answers {
+      for (k, chunk) in puzzles.chunks(self.answers_per_page).enumerate() {
+        let grids = chunk.iter().enumerate().map(|(i, (puzzle, solution))| {
+          (format!("Answer {}", k * self.answers_per_page + i + 1), puzzle, solution)
+        }).collect::<Vec<_>>();
+        pages.push((format!("{} - Answers", self.title), grids, self.answers_per_page));
+      }
+    }
+
+    // Fixed objects first, then a page and its content stream for every page
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let info_id = Ref::new(3);
+    let regular_font_id = Ref::new(4);
+    let bold_font_id = Ref::new(5);
+    let page_ids = (0..pages.len()).map(|k| Ref::new(6 + 2 * k as i32)).collect::<Vec<_>>();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id).kids(page_ids.iter().copied()).count(page_ids.len() as i32);
+    pdf.document_info(info_id).title(TextStr(&self.title)).creator(TextStr("sudoku-rs"));
+    pdf.type1_font(regular_font_id).base_font(Name(b"Helvetica")).encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_font_id).base_font(Name(b"Helvetica-Bold")).encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    for (k, (header, grids, per_page)) in pages.iter().enumerate() {
+      let content_id = Ref::new(page_ids[k].get() + 1);
+      let mut page = pdf.page(page_ids[k]);
+      page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+      page.parent(page_tree_id);
+      page.contents(content_id);
+      page.resources().fonts().pair(REGULAR_FONT, regular_font_id).pair(BOLD_FONT, bold_font_id);
+      page.finish();
+
+      let mut content = Content::new();
+      centered_text(&mut content, header, 14.0, PAGE_HEIGHT - MARGIN - 14.0);
+      centered_text(&mut content, &(k + 1).to_string(), 10.0, MARGIN - 10.0);
+      for ((label, puzzle, board), area) in grids.iter().zip(grid_areas(*per_page)) {
+        draw_grid(&mut content, label, *puzzle, *board, area);
+      }
+      pdf.stream(content_id, &content.finish());
+    }
+    pdf.finish()
+  }
+}
+
+/// The square areas `(x, y, size)` of the grids on a page holding `n` grids,
+/// from the top left to the bottom right
+fn grid_areas(n: usize) -> Vec<(f32, f32, f32)> {
+  let columns = ((n as f64).sqrt().floor() as usize).max(1);
+  let rows = n.div_ceil(columns);
+  let width = (PAGE_WIDTH - 2.0 * MARGIN) / columns as f32;
+  let height = (PAGE_HEIGHT - 2.0 * MARGIN - HEADER_HEIGHT - FOOTER_HEIGHT) / rows as f32;
+  let size = (width - LABEL_HEIGHT).min(height - 2.0 * LABEL_HEIGHT);
+  let top = PAGE_HEIGHT - MARGIN - HEADER_HEIGHT;
+  (0..n).map(|k| {
+    let (row, column) = (k / columns, k % columns);
+    let x = MARGIN + width * column as f32 + (width - size) / 2.0;
+    let y = top - height * row as f32 - LABEL_HEIGHT - size;
+    (x, y, size)
+  }).collect()
+}
+
+/// Draw a grid with its label above it. Givens of `puzzle` are in bold, and
+/// the other known slots of `board` in gray
+fn draw_grid<B: Board>(content: &mut Content, label: &str, puzzle: &B, board: &B, (x, y, size): (f32, f32, f32)) {
+  let (n, block_size) = (B::size(), B::block_size());
+  let cell = size / n as f32;
+
+  content.begin_text();
+  content.set_font(REGULAR_FONT, 11.0);
+  content.next_line(x, y + size + 6.0);
+  content.show(Str(&encode(label)));
+  content.end_text();
+
+  // Thin lines between slots, thick lines between blocks
+  for k in 0..=n {
+    let offset = cell * k as f32;
+    content.set_line_width(if k % block_size == 0 { size / 150.0 } else { size / 600.0 });
+    content.move_to(x + offset, y);
+    content.line_to(x + offset, y + size);
+    content.move_to(x, y + offset);
+    content.line_to(x + size, y + offset);
+    content.stroke();
+  }
+
+  let font_size = cell * 0.6;
+  for slot in B::slots() {
+    let digit : u8 = (*board.get(&slot)).into();
+    if digit == 0 {
+      continue;
+    }
+    let (font, gray) = if puzzle.get(&slot).is_answer() { (BOLD_FONT, 0.0) } else { (REGULAR_FONT, 0.4) };
+    let text = digit.to_string();
+    let cx = x + cell * (slot.1 as f32 + 0.5);
+    let cy = y + size - cell * (slot.0 as f32 + 0.5);
+    content.begin_text();
+    content.set_fill_rgb(gray, gray, gray);
+    content.set_font(font, font_size);
+    content.next_line(cx - text_width(&text, font_size) / 2.0, cy - font_size * 0.36);
+    content.show(Str(text.as_bytes()));
+    content.end_text();
+  }
+  content.set_fill_rgb(0.0, 0.0, 0.0);
+}
+
+fn centered_text(content: &mut Content, text: &str, font_size: f32, y: f32) {
+  content.begin_text();
+  content.set_font(REGULAR_FONT, font_size);
+  content.next_line((PAGE_WIDTH - text_width(text, font_size)) / 2.0, y);
+  content.show(Str(&encode(text)));
+  content.end_text();
+}
+
+/// Encode text in the WinAnsi encoding of the fonts, replacing what it cannot
+/// represent with `?`
+fn encode(text: &str) -> Vec<u8> {
+  text.chars().map(|c| if (c as u32) < 0x80 || (0xa0..=0xff).contains(&(c as u32)) { c as u8 } else { b'?' }).collect()
+}
+
+fn capitalize(s: &str) -> String {
+  let mut chars = s.chars();
+  match chars.next() {
+    Some(c) => c.to_uppercase().chain(chars).collect(),
+    None => String::new(),
+  }
+}
+
+/// Width of a text set in Helvetica. Characters outside of printable ASCII
+/// count as wide as a digit
+fn text_width(text: &str, font_size: f32) -> f32 {
+  const WIDTHS : [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+  ];
+  let units = text.chars().map(|c| match c as usize {
+    code @ 32..=126 => WIDTHS[code - 32] as f32,
+    _ => 556.0,
+  }).sum::<f32>();
+  units * font_size / 1000.0
+}
@@ -0,0 +1,177 @@
+use crate::board::*;
+use crate::transform::Transform;
+
+/// Find the lexicographically minimal board among all the boards equivalent to
+/// `board` under the sudoku symmetry group (digit relabeling, row/band and
+/// column/stack permutations, and transposition). Boards are compared in row
+/// major order, with unknowns being smaller than any digit.
+///
+/// Returns the minimal board and the transform mapping `board` onto it.
+///
+/// Algorithm: for every transposition and column permutation, the rows are
+/// picked one at a time with a depth first search. Digits are relabeled in
+/// order of first appearance, which is the minimal relabeling for a fixed
+/// placement of the cells. A branch is pruned as soon as the rows picked so far
+/// are larger than the best board found.
+pub fn canonical_form<B: Board>(board: &B) -> (B, Transform) {
+  let size = B::size();
+  let grid = board.to_u8_vec();
+  let mut search = CanonicalSearch {
+    size,
+    block_size: B::block_size(),
+    source: vec![],
+    columns: vec![],
+    best: vec![None; size],
+    best_transform: None,
+    row_order: vec![],
+    transposed: false,
+  };
+  for transposed in [false, true] {
+    search.transposed = transposed;
+    search.source = (0..size * size).map(|k| {
+      let (i, j) = (k / size, k % size);
+      if transposed { grid[j * size + i] } else { grid[i * size + j] }
+    }).collect();
+    for columns in line_permutations(size, B::block_size()) {
+      search.columns = columns;
+      search.search_rows(0, &mut vec![false; size], &vec![0; size + 1], 1, false);
+    }
+  }
+  let transform = search.best_transform.expect("Canonical search always reaches a full board");
+  (transform.apply(board), transform)
+}
+
+/// All the permutations of `size` lines that keep lines within their group of
+/// `block_size` lines. `perm[k]` is the original line placed at position `k`
+fn line_permutations(size: usize, block_size: usize) -> Vec<Vec<usize>> {
+  let within = permutations(block_size);
+  let mut result = vec![];
+  for group_order in permutations(size / block_size) {
+    let mut partial = vec![vec![]];
+    for group in group_order {
+      let mut next = vec![];
+      for prefix in &partial {
+        for lines in &within {
+          let mut perm : Vec<usize> = prefix.clone();
+          perm.extend(lines.iter().map(|k| group * block_size + k));
+          next.push(perm);
+        }
+      }
+      partial = next;
+    }
+    result.extend(partial);
+  }
+  result
+}
+
+/// All the permutations of `0..n`
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+  if n == 0 {
+    return vec![vec![]];
+  }
+  let mut result = vec![];
+  for perm in permutations(n - 1) {
+    for k in 0..n {
+      let mut perm = perm.clone();
+      perm.insert(k, n - 1);
+      result.push(perm);
+    }
+  }
+  result
+}
+
+struct CanonicalSearch {
+  size: usize,
+  block_size: usize,
+  /// The (possibly transposed) grid being searched on, in row major order
+  source: Vec<u8>,
+  /// `columns[l]` is the source column placed at column `l`
+  columns: Vec<usize>,
+  /// The rows of the best board so far, `None` stands for not found yet
+  best: Vec<Option<Vec<u8>>>,
+  best_transform: Option<Transform>,
+  /// `row_order[k]` is the source row placed at row `k`
+  row_order: Vec<usize>,
+  transposed: bool,
+}
+
+impl CanonicalSearch {
+  fn search_rows(&mut self, k: usize, used: &mut [bool], digits: &[u8], next_label: u8, improved: bool) {
+    if k == self.size {
+      if improved {
+        self.best_transform = Some(self.transform(digits, next_label));
+      }
+      return;
+    }
+
+    // Rows of a new band can come from any unused band, otherwise the rows
+    // have to come from the band currently being filled
+    let block_size = self.block_size;
+    let candidates = if k.is_multiple_of(block_size) {
+      (0..self.size).filter(|r| {
+        let band = r / block_size;
+        !used[band * block_size..(band + 1) * block_size].iter().any(|u| *u)
+      }).collect::<Vec<_>>()
+    } else {
+      let band = self.row_order[k - 1] / block_size;
+      (band * block_size..(band + 1) * block_size).filter(|r| !used[*r]).collect()
+    };
+
+    for row in candidates {
+      // Relabel the row, assigning labels to digits appearing for the first time
+      let mut row_digits = digits.to_vec();
+      let mut label = next_label;
+      let values = self.columns.iter().map(|col| {
+        let value = self.source[row * self.size + col];
+        if value != 0 && row_digits[value as usize] == 0 {
+          row_digits[value as usize] = label;
+          label += 1;
+        }
+        row_digits[value as usize]
+      }).collect::<Vec<_>>();
+
+      // Compare against the best board found so far
+      let now_improved = match &self.best[k] {
+        Some(best_row) => {
+          if values > *best_row { continue; }
+          values < *best_row
+        },
+        None => true,
+      };
+      if now_improved {
+        self.best[k] = Some(values);
+        for later in self.best.iter_mut().skip(k + 1) { *later = None; }
+      }
+
+      used[row] = true;
+      self.row_order.push(row);
+      self.search_rows(k + 1, used, &row_digits, label, improved || now_improved);
+      self.row_order.pop();
+      used[row] = false;
+    }
+  }
+
+  /// Build the transform for the rows currently picked
+  fn transform(&self, digits: &[u8], next_label: u8) -> Transform {
+    // Digits that never appear get the remaining labels in increasing order
+    let mut digits = digits.to_vec();
+    let mut label = next_label;
+    for digit in digits.iter_mut().skip(1) {
+      if *digit == 0 {
+        *digit = label;
+        label += 1;
+      }
+    }
+    let invert = |perm: &[usize]| {
+      let mut result = vec![0; perm.len()];
+      for (i, p) in perm.iter().enumerate() { result[*p] = i; }
+      result
+    };
+    let (rows, columns) = (invert(&self.row_order), invert(&self.columns));
+    if self.transposed {
+      Transform::from_parts(digits, columns, rows, true)
+    } else {
+      Transform::from_parts(digits, rows, columns, false)
+    }
+  }
+}
@@ -0,0 +1,69 @@
+use crate::board::*;
+
+/// The result of checking the entries of a player against a puzzle. See
+/// `Board::check_entries`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryReport {
+  /// The slots holding a given of the puzzle
+  pub givens: Vec<Slot>,
+
+  /// The slots filled in by the player
+  pub entries: Vec<Slot>,
+
+  /// The slots that are still empty
+  pub empty: Vec<Slot>,
+
+  /// The entries that are wrong. When the puzzle has a unique solution, these
+  /// are the entries disagreeing with it. Otherwise, these are the entries
+  /// that do not appear in any solution of the puzzle
+  pub wrong: Vec<Slot>,
+
+  /// Whether the puzzle has a unique solution
+  pub unique: bool,
+
+  /// Whether the givens and all the entries together still have a solution
+  pub solvable: bool,
+}
+
+impl EntryReport {
+  /// Whether the player filled in every slot correctly
+  pub fn is_solved(&self) -> bool {
+    self.empty.is_empty() && self.wrong.is_empty() && self.solvable
+  }
+}
+
+pub(crate) fn check_entries<B: Board>(puzzle: &B, entries: &B) -> Result<EntryReport, String> {
+  if let Err(conflicts) = puzzle.validate() {
+    return Err(format!("The puzzle is invalid: {}", conflicts[0]));
+  }
+  let mut solutions = puzzle.solve_with_ctx(SolvingContext::deterministic());
+  let solution = solutions.next().ok_or("The puzzle has no solution")?;
+  let unique = solutions.next().is_none();
+
+  let mut report = EntryReport { givens: vec![], entries: vec![], empty: vec![], wrong: vec![], unique, solvable: false };
+  for slot in B::slots() {
+    let (given, entry) = (puzzle.get(&slot), entries.get(&slot));
+    if given.is_answer() {
+      if entry != given {
+        return Err(format!("Slot ({}, {}) changes a given of the puzzle", slot.0, slot.1));
+      }
+      report.givens.push(slot);
+    } else if entry.is_answer() {
+      report.entries.push(slot);
+      let wrong = if unique {
+        entry != solution.get(&slot)
+      } else {
+        let mut board = puzzle.clone();
+        board.set(&slot, *entry);
+        board.solve_with_ctx(SolvingContext::deterministic()).next().is_none()
+      };
+      if wrong {
+        report.wrong.push(slot);
+      }
+    } else {
+      report.empty.push(slot);
+    }
+  }
+  report.solvable = report.wrong.is_empty() && entries.solve_with_ctx(SolvingContext::deterministic()).next().is_some();
+  Ok(report)
+}
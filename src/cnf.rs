@@ -0,0 +1,188 @@
+use crate::board::*;
+use crate::variant::*;
+
+/// Options of the CNF encoding of a board
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CnfOptions {
+  /// Also require every digit to appear exactly once on both diagonals, as
+  /// two `Constraint::Diagonal` would
+  pub diagonals: bool,
+}
+
+impl CnfOptions {
+  /// The variant constraints added by the options
+  pub fn constraints(&self) -> Vec<Constraint> {
+    if self.diagonals {
+      vec![Constraint::Diagonal(Diagonal::Negative), Constraint::Diagonal(Diagonal::Positive)]
+    } else {
+      vec![]
+    }
+  }
+}
+
+/// A boolean formula in conjunctive normal form. Variables are numbered from
+/// `1` to `num_variables`, and a clause is a list of literals: `v` for the
+/// variable `v` being true, `-v` for it being false.
+///
+/// The encoding of a board uses one variable per slot and digit, being true
+/// when the slot holds the digit (see `Cnf::variable`).
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let cnf = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).to_cnf();
+/// assert_eq!(cnf.num_variables, 64);
+/// assert!(cnf.to_dimacs().starts_with("p cnf 64 "));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cnf {
+  pub num_variables: usize,
+  pub clauses: Vec<Vec<i32>>,
+}
+
+impl Cnf {
+  /// The variable telling that `slot` holds `digit` (from `1` to `size`)
+  pub fn variable<B: Board>(slot: &Slot, digit: u8) -> i32 {
+    ((slot.0 * B::size() + slot.1) * B::size() + digit as usize) as i32
+  }
+
+  /// Encode a board: every slot holds exactly one digit, every digit appears
+  /// exactly once in every row, column and block (and diagonal, if asked),
+  /// and the known slots hold their digit
+  pub fn encode<B: Board>(board: &B, options: &CnfOptions) -> Self {
+    options.constraints().into_iter().fold(VariantPuzzle::new(board.clone()), VariantPuzzle::constraint).to_cnf()
+  }
+
+  /// Encode a board where every digit appears exactly once in each of the
+  /// given units (at most once in units smaller than the board)
+  pub(crate) fn encode_units<B: Board>(board: &B, units: &[Vec<Slot>]) -> Self {
+    let size = B::size();
+    let mut cnf = Self { num_variables: size * size * size, clauses: vec![] };
+
+    // Every slot holds exactly one digit
+    for slot in B::slots() {
+      let vars = (1..=size as u8).map(|d| Self::variable::<B>(&slot, d)).collect::<Vec<_>>();
+      cnf.add_exactly_one(&vars);
+    }
+
+    // Every digit appears exactly once in every unit
+    for unit in units {
+      for d in 1..=size as u8 {
+        let vars = unit.iter().map(|slot| Self::variable::<B>(slot, d)).collect::<Vec<_>>();
+        if unit.len() == size {
+          cnf.add_exactly_one(&vars);
+        } else {
+          cnf.add_at_most_one(&vars);
+        }
+      }
+    }
+
+    // The known slots hold their digit
+    for slot in B::slots() {
+      let digit : u8 = (*board.get(&slot)).into();
+      if digit != 0 {
+        cnf.clauses.push(vec![Self::variable::<B>(&slot, digit)]);
+      }
+    }
+    cnf
+  }
+
+  /// Add a variable other than the ones of `Cnf::variable`, e.g. to encode
+  /// sums. Returns the new variable
+  pub(crate) fn new_variable(&mut self) -> i32 {
+    self.num_variables += 1;
+    self.num_variables as i32
+  }
+
+  /// The clause ruling out a solution
+  pub fn blocking_clause<B: Board>(solution: &B) -> Vec<i32> {
+    B::slots().map(|slot| -Self::variable::<B>(&slot, (*solution.get(&slot)).into())).collect()
+  }
+
+  /// Rule out the digits that are not among the candidates of their slot, the
+  /// candidates being given for every slot in row major order
+  pub fn add_candidates<B: Board>(&mut self, candidates: &[Vec<u8>]) {
+    for (slot, candidates) in B::slots().zip(candidates) {
+      for d in (1..=B::size() as u8).filter(|d| !candidates.contains(d)) {
+        self.clauses.push(vec![-Self::variable::<B>(&slot, d)]);
+      }
+    }
+  }
+
+  /// Add the clauses telling that exactly one of `vars` is true
+  fn add_exactly_one(&mut self, vars: &[i32]) {
+    self.clauses.push(vars.to_vec());
+    self.add_at_most_one(vars);
+  }
+
+  /// Add the clauses telling that at most one of `vars` is true
+  pub(crate) fn add_at_most_one(&mut self, vars: &[i32]) {
+    for (k, a) in vars.iter().enumerate() {
+      for b in &vars[k + 1..] {
+        self.clauses.push(vec![-a, -b]);
+      }
+    }
+  }
+
+  /// Write the formula in DIMACS format
+  pub fn to_dimacs(&self) -> String {
+    let mut s = format!("p cnf {} {}\n", self.num_variables, self.clauses.len());
+    for clause in &self.clauses {
+      for lit in clause {
+        s += &lit.to_string();
+        s += " ";
+      }
+      s += "0\n";
+    }
+    s
+  }
+
+  /// Turn a model of the encoding of a board (the literals being true) back
+  /// into a board. Every slot needs exactly one of its digits to be true
+  pub fn decode<B: Board>(model: &[i32]) -> Result<B, String> {
+    let size = B::size();
+    let mut digits = vec![0; size * size];
+    for lit in model.iter().filter(|lit| **lit > 0) {
+      let var = *lit as usize - 1;
+      if var >= size * size * size {
+        continue;
+      }
+      let (k, digit) = (var / size, (var % size + 1) as u8);
+      if digits[k] != 0 {
+        return Err(format!("Slot ({}, {}) holds more than one digit in the model", k / size, k % size));
+      }
+      digits[k] = digit;
+    }
+    if let Some(k) = digits.iter().position(|d| *d == 0) {
+      return Err(format!("Slot ({}, {}) holds no digit in the model", k / size, k % size));
+    }
+    Ok(B::from_u8_vec(&digits))
+  }
+}
+
+/// Parse the output of a SAT solver into a model. Both the competition format
+/// (`s SATISFIABLE` followed by `v` lines) and the MiniSat format (`SAT`
+/// followed by the literals) are accepted. Returns `Err` when the formula is
+/// unsatisfiable
+pub fn parse_model(output: &str) -> Result<Vec<i32>, String> {
+  let mut model = vec![];
+  for line in output.lines().map(str::trim) {
+    let values = match line.split_whitespace().next() {
+      None | Some("c") | Some("s") | Some("SAT") => {
+        if line.contains("UNSAT") {
+          return Err("The formula is unsatisfiable".to_string());
+        }
+        continue;
+      },
+      Some("UNSAT") => return Err("The formula is unsatisfiable".to_string()),
+      Some("v") => &line[1..],
+      Some(_) => line,
+    };
+    for value in values.split_whitespace() {
+      let lit = value.parse::<i32>().map_err(|_| format!("Invalid literal `{}` in SAT solver output", value))?;
+      if lit != 0 {
+        model.push(lit);
+      }
+    }
+  }
+  Ok(model)
+}
@@ -0,0 +1,65 @@
+use std::str::FromStr;
+
+use crate::board::*;
+
+/// How hard a puzzle is for a human, judged by the techniques needed to solve
+/// it. See `Board::difficulty`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+  /// Naked singles (slots with one possible answer) are enough
+  Easy,
+
+  /// Hidden singles (digits with one possible slot in a unit) are needed
+  Medium,
+
+  /// Guessing is needed, but a wrong guess is found out quickly
+  Hard,
+
+  /// Deep guessing is needed
+  Expert,
+}
+
+/// Maximum number of guesses needed by the search for a `Hard` puzzle
+const MAX_HARD_GUESSES : usize = 8;
+
+impl Difficulty {
+  pub fn all() -> [Difficulty; 4] {
+    [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Expert]
+  }
+}
+
+impl std::fmt::Display for Difficulty {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let name = match self {
+      Difficulty::Easy => "easy",
+      Difficulty::Medium => "medium",
+      Difficulty::Hard => "hard",
+      Difficulty::Expert => "expert",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+impl FromStr for Difficulty {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Difficulty::all().iter().copied().find(|d| d.to_string() == s).ok_or_else(|| format!("Unknown difficulty {}", s))
+  }
+}
+
+pub(crate) fn rate<B: Board>(board: &B) -> Difficulty {
+  let mut naked = board.clone();
+  while naked.put_determined_answers() == FillResult::Modified {}
+  if !naked.has_unknown_slot() {
+    return Difficulty::Easy;
+  }
+  let mut propagated = naked;
+  propagated.propagate();
+  if !propagated.has_unknown_slot() {
+    return Difficulty::Medium;
+  }
+  let mut solutions = board.solve_with_ctx(SolvingContext::deterministic());
+  solutions.next();
+  if solutions.stats().guesses <= MAX_HARD_GUESSES { Difficulty::Hard } else { Difficulty::Expert }
+}
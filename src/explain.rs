@@ -0,0 +1,93 @@
+use crate::board::*;
+
+/// Two known slots holding the same digit inside a unit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+  pub digit: u8,
+  pub unit: Unit,
+  pub slots: (Slot, Slot),
+}
+
+impl std::fmt::Display for Conflict {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let ((i1, j1), (i2, j2)) = self.slots;
+    write!(f, "digit {} appears twice in {}, at ({}, {}) and ({}, {})", self.digit, self.unit, i1, j1, i2, j2)
+  }
+}
+
+/// The reason why a board has no solution
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnsatExplanation {
+  /// Known slots directly conflicting with each other
+  Conflicts(Vec<Conflict>),
+
+  /// A minimal set of known slots (with their digits) that has no solution on
+  /// its own, although no two of them conflict. Removing any of them gives a
+  /// board with a solution
+  Core(Vec<(Slot, u8)>),
+}
+
+impl std::fmt::Display for UnsatExplanation {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      UnsatExplanation::Conflicts(conflicts) => {
+        let conflicts = conflicts.iter().map(ToString::to_string).collect::<Vec<_>>();
+        write!(f, "{}", conflicts.join("; "))
+      },
+      UnsatExplanation::Core(core) => {
+        let givens = core.iter().map(|((i, j), digit)| format!("({}, {}) = {}", i, j, digit)).collect::<Vec<_>>();
+        write!(f, "these {} givens have no solution together: {}", core.len(), givens.join(", "))
+      },
+    }
+  }
+}
+
+/// Find the pairs of known slots holding the same digit, unit by unit
+pub(crate) fn conflicts<B: Board>(board: &B) -> Vec<Conflict> {
+  let mut result = vec![];
+  for unit in B::units() {
+    let slots = unit.slots::<B>();
+    for (k, a) in slots.iter().enumerate() {
+      let digit : u8 = (*board.get(a)).into();
+      if digit == 0 {
+        continue;
+      }
+      for b in &slots[k + 1..] {
+        if *board.get(b) == *board.get(a) {
+          result.push(Conflict { digit, unit, slots: (*a, *b) });
+        }
+      }
+    }
+  }
+  result
+}
+
+/// Algorithm: conflicts among the known slots explain the board directly.
+/// Otherwise, the known slots are dropped one at a time, putting a slot back
+/// whenever dropping it makes the board solvable. As dropping known slots
+/// never removes solutions, the slots left form a minimal unsatisfiable core
+pub(crate) fn explain_unsat<B: Board>(board: &B) -> Option<UnsatExplanation> {
+  let conflicts = conflicts(board);
+  if !conflicts.is_empty() {
+    return Some(UnsatExplanation::Conflicts(conflicts));
+  }
+  if has_solution(board) {
+    return None;
+  }
+  let mut core = board.clone();
+  for slot in B::slots() {
+    let elem = *core.get(&slot);
+    if elem.is_answer() {
+      core.set(&slot, B::Element::default());
+      if has_solution(&core) {
+        core.set(&slot, elem);
+      }
+    }
+  }
+  let givens = B::slots().filter(|slot| core.get(slot).is_answer()).map(|slot| (slot, (*core.get(&slot)).into())).collect();
+  Some(UnsatExplanation::Core(givens))
+}
+
+fn has_solution<B: Board>(board: &B) -> bool {
+  board.solve_with_ctx(SolvingContext::deterministic()).next().is_some()
+}
@@ -0,0 +1,235 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::board::*;
+
+/// The state that undo and redo go back and forth between
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct GameState<B> where B : Board {
+  board: B,
+  /// `marks[k]` has bit `d` set when digit `d` is pencil marked in slot `k`
+  marks: Vec<u32>,
+}
+
+/// A game of sudoku being played: the puzzle, the digits entered by the
+/// player, pencil marks, a cursor, and an undo history.
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let puzzle = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]);
+/// let mut game = Game::new(puzzle).unwrap();
+/// game.move_cursor(0, 1);
+/// game.enter(3);
+/// assert_eq!(game.get(&(0, 1)), 3);
+/// game.undo();
+/// assert_eq!(game.get(&(0, 1)), 0);
+/// ```
+pub struct Game<B> where B : Board {
+  puzzle: B,
+  solution: B,
+  state: GameState<B>,
+  cursor: Slot,
+  undo_stack: Vec<GameState<B>>,
+  redo_stack: Vec<GameState<B>>,
+}
+
+impl<B> Game<B> where B : Board {
+  /// Start a game on a puzzle, which needs to have a unique solution so that
+  /// the entries can be judged against it
+  pub fn new(puzzle: B) -> Result<Self, String> {
+    let mut solutions = puzzle.solve_with_ctx(SolvingContext::deterministic());
+    let solution = match solutions.next() {
+      Some(solution) => solution,
+      None => return Err(match puzzle.explain_unsat() {
+        Some(explanation) => format!("The puzzle has no solution: {}", explanation),
+        None => "The puzzle has no solution".to_string(),
+      }),
+    };
+    if solutions.next().is_some() {
+      return Err("The puzzle has more than one solution".to_string());
+    }
+    Self::with_solution(puzzle, solution)
+  }
+
+  /// Start a game on a puzzle along with its solution, e.g. the one stored
+  /// next to the puzzle in a file. The puzzle may have other solutions, the
+  /// entries being judged against the given one
+  pub fn with_solution(puzzle: B, solution: B) -> Result<Self, String> {
+    if solution.has_unknown_slot() {
+      return Err("The solution has unknown slots".to_string());
+    }
+    if let Err(conflicts) = solution.validate() {
+      return Err(format!("The solution is invalid: {}", conflicts[0]));
+    }
+    if B::slots().any(|slot| puzzle.get(&slot).is_answer() && puzzle.get(&slot) != solution.get(&slot)) {
+      return Err("The solution does not agree with the puzzle".to_string());
+    }
+    let state = GameState { board: puzzle.clone(), marks: vec![0; B::size() * B::size()] };
+    Ok(Self { puzzle, solution, state, cursor: (0, 0), undo_stack: vec![], redo_stack: vec![] })
+  }
+
+  /// Start a game on a random puzzle with (at most) `num_unknowns` unknown
+  /// slots and a unique solution
+  pub fn random<R: Rng + ?Sized>(num_unknowns: usize, rng: &mut R) -> Self {
+    Self::new(generate_puzzle(num_unknowns, rng)).expect("Generated puzzles have a solution")
+  }
+
+  pub fn puzzle(&self) -> &B {
+    &self.puzzle
+  }
+
+  /// The board with the givens and the digits entered so far
+  pub fn board(&self) -> &B {
+    &self.state.board
+  }
+
+  pub fn cursor(&self) -> Slot {
+    self.cursor
+  }
+
+  /// Move the cursor by the given number of rows and columns, wrapping around
+  /// the edges of the board
+  pub fn move_cursor(&mut self, rows: isize, columns: isize) {
+    let size = B::size() as isize;
+    let wrap = |x: usize, dx: isize| (x as isize + dx).rem_euclid(size) as usize;
+    self.cursor = (wrap(self.cursor.0, rows), wrap(self.cursor.1, columns));
+  }
+
+  /// The digit at a slot, `0` for empty
+  pub fn get(&self, slot: &Slot) -> u8 {
+    (*self.state.board.get(slot)).into()
+  }
+
+  /// Check if a slot holds a given of the puzzle
+  pub fn is_given(&self, slot: &Slot) -> bool {
+    self.puzzle.get(slot).is_answer()
+  }
+
+  /// The digits pencil marked in a slot, in increasing order
+  pub fn marks(&self, slot: &Slot) -> Vec<u8> {
+    let marks = self.state.marks[slot.0 * B::size() + slot.1];
+    (1..=B::size() as u8).filter(|d| marks >> d & 1 == 1).collect()
+  }
+
+  /// Enter a digit at the cursor, clearing its pencil marks. Returns `false`
+  /// when the slot holds a given
+  pub fn enter(&mut self, digit: u8) -> bool {
+    let slot = self.cursor;
+    if self.is_given(&slot) || digit == 0 || digit as usize > B::size() || self.get(&slot) == digit {
+      return false;
+    }
+    self.record();
+    self.state.board.set(&slot, B::Element::from(digit));
+    self.state.marks[slot.0 * B::size() + slot.1] = 0;
+    true
+  }
+
+  /// Clear the digit and the pencil marks at the cursor. Returns `false` when
+  /// there is nothing to clear
+  pub fn clear(&mut self) -> bool {
+    let slot = self.cursor;
+    let k = slot.0 * B::size() + slot.1;
+    if self.is_given(&slot) || (self.get(&slot) == 0 && self.state.marks[k] == 0) {
+      return false;
+    }
+    self.record();
+    self.state.board.set(&slot, B::Element::default());
+    self.state.marks[k] = 0;
+    true
+  }
+
+  /// Add or remove a pencil mark at the cursor. Returns `false` when the slot
+  /// is not empty
+  pub fn toggle_mark(&mut self, digit: u8) -> bool {
+    let slot = self.cursor;
+    if self.get(&slot) != 0 || digit == 0 || digit as usize > B::size() {
+      return false;
+    }
+    self.record();
+    self.state.marks[slot.0 * B::size() + slot.1] ^= 1 << digit;
+    true
+  }
+
+  /// Fill in one slot with its answer: the slot under the cursor if it is
+  /// empty or wrong, otherwise the first wrong entry, otherwise the empty slot
+  /// with the least possible answers. Returns the slot being filled in
+  pub fn hint(&mut self) -> Option<Slot> {
+    let needs_answer = |slot: &Slot| self.state.board.get(slot) != self.solution.get(slot);
+    let slot = if needs_answer(&self.cursor) {
+      self.cursor
+    } else {
+      let wrong = B::slots().find(|slot| self.get(slot) != 0 && needs_answer(slot));
+      let empty = || self.state.board.unknown_slots().into_iter().min_by_key(|slot| self.state.board.possible_answers(slot).count());
+      wrong.or_else(empty)?
+    };
+    self.record();
+    self.state.board.set(&slot, *self.solution.get(&slot));
+    self.state.marks[slot.0 * B::size() + slot.1] = 0;
+    Some(slot)
+  }
+
+  /// Undo the last change. Returns `false` when there is nothing to undo
+  pub fn undo(&mut self) -> bool {
+    match self.undo_stack.pop() {
+      Some(state) => {
+        self.redo_stack.push(std::mem::replace(&mut self.state, state));
+        true
+      },
+      None => false,
+    }
+  }
+
+  /// Redo the last undone change. Returns `false` when there is nothing to redo
+  pub fn redo(&mut self) -> bool {
+    match self.redo_stack.pop() {
+      Some(state) => {
+        self.undo_stack.push(std::mem::replace(&mut self.state, state));
+        true
+      },
+      None => false,
+    }
+  }
+
+  /// The slots holding the same digit as another slot of one of their units
+  pub fn conflicting_slots(&self) -> Vec<Slot> {
+    let mut slots = self.state.board.conflicts().into_iter().flat_map(|conflict| vec![conflict.slots.0, conflict.slots.1]).collect::<Vec<_>>();
+    slots.sort_unstable();
+    slots.dedup();
+    slots
+  }
+
+  /// Check if the board is complete and correct
+  pub fn is_solved(&self) -> bool {
+    self.state.board == self.solution
+  }
+
+  /// Save the current state before a change
+  fn record(&mut self) {
+    self.undo_stack.push(self.state.clone());
+    self.redo_stack.clear();
+  }
+}
+
+/// Generate a puzzle with a unique solution: starting from a random complete
+/// grid, slots are emptied in random order as long as the solution stays
+/// unique, until `num_unknowns` slots are empty or no slot can be emptied
+pub fn generate_puzzle<B: Board, R: Rng + ?Sized>(num_unknowns: usize, rng: &mut R) -> B {
+  let ctx = SolvingContext::random_with_seed(rng.gen());
+  let mut puzzle = B::default().solve_with_ctx(ctx).next().expect("The empty board has a solution");
+  let mut slots = B::slots().collect::<Vec<_>>();
+  slots.shuffle(rng);
+  let mut remaining = num_unknowns;
+  for slot in slots {
+    if remaining == 0 {
+      break;
+    }
+    let elem = *puzzle.get(&slot);
+    puzzle.set(&slot, B::Element::default());
+    if puzzle.solve_with_ctx(SolvingContext::deterministic()).take(2).count() == 1 {
+      remaining -= 1;
+    } else {
+      puzzle.set(&slot, elem);
+    }
+  }
+  puzzle
+}
@@ -0,0 +1,230 @@
+use rand::seq::SliceRandom;
+use std::str::FromStr;
+
+use crate::board::*;
+use crate::rng::SolverRng;
+
+/// A board as seen by a `Heuristic`, independently of its size. Digits are
+/// given as `u8`, where `0` stands for unknown
+pub trait SearchNode {
+  /// The overall size of the board
+  fn size(&self) -> usize;
+
+  /// The size of a block inside the board
+  fn block_size(&self) -> usize;
+
+  /// The digit at the given slot
+  fn get(&self, slot: &Slot) -> u8;
+
+  /// The number of possible answers at the given slot
+  fn num_candidates(&self, slot: &Slot) -> usize;
+
+  /// The possible answers at the given slot, in increasing order
+  fn candidates(&self, slot: &Slot) -> Vec<u8>;
+
+  /// All the unknown slots, in row major order
+  fn unknown_slots(&self) -> Vec<Slot> {
+    let size = self.size();
+    (0..size * size).map(|k| (k / size, k % size)).filter(|slot| self.get(slot) == 0).collect()
+  }
+
+  /// All the units (e.g. rows, columns, and blocks) inside which digits do not
+  /// repeat, each of them as a list of slots
+  fn units(&self) -> Vec<Vec<Slot>>;
+
+  /// The slots sharing a unit with the given slot, in row major order
+  fn peers(&self, slot: &Slot) -> Vec<Slot> {
+    let mut peers = self.units().into_iter()
+      .filter(|unit| unit.contains(slot))
+      .flatten()
+      .filter(|other| other != slot)
+      .collect::<Vec<_>>();
+    peers.sort_unstable();
+    peers.dedup();
+    peers
+  }
+}
+
+/// The `SearchNode` view of a board
+pub(crate) struct BoardNode<'a, B>(pub &'a B) where B : Board;
+
+impl<'a, B> SearchNode for BoardNode<'a, B> where B : Board {
+  fn size(&self) -> usize {
+    B::size()
+  }
+
+  fn block_size(&self) -> usize {
+    B::block_size()
+  }
+
+  fn get(&self, slot: &Slot) -> u8 {
+    (*self.0.get(slot)).into()
+  }
+
+  fn num_candidates(&self, slot: &Slot) -> usize {
+    self.0.possible_answers(slot).count()
+  }
+
+  fn candidates(&self, slot: &Slot) -> Vec<u8> {
+    self.0.possible_answers(slot).elements().into_iter().map(Into::into).collect()
+  }
+
+  fn unknown_slots(&self) -> Vec<Slot> {
+    self.0.unknown_slots()
+  }
+
+  fn units(&self) -> Vec<Vec<Slot>> {
+    B::units().iter().map(Unit::slots::<B>).collect()
+  }
+}
+
+/// A branching heuristic of the backtracking search: which unknown slot to
+/// branch on, and in which order its possible answers are tried.
+///
+/// `rng` is the random generator of the `SolvingContext`, `None` for a
+/// deterministic search.
+pub trait Heuristic : Send + Sync {
+  /// Pick the slot to branch on among the unknown slots of `node`. Returns
+  /// `None` when there is no unknown slot
+  fn select_slot(&self, node: &dyn SearchNode, rng: Option<&mut SolverRng>) -> Option<Slot>;
+
+  /// Sort the possible answers of `slot`, the first one being tried first
+  fn order_values(&self, node: &dyn SearchNode, slot: &Slot, values: &mut Vec<u8>, rng: Option<&mut SolverRng>);
+}
+
+/// How a `Strategy` picks the slot to branch on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlotSelection {
+  /// The first unknown slot in row major order
+  FirstEmpty,
+
+  /// The slot with the least possible answers
+  MinimumRemainingValues,
+
+  /// The slot with the least possible answers, ties being broken by the most
+  /// unknown peers
+  MinimumRemainingValuesDegree,
+
+  /// The slot with the least possible answers inside the unit (row, column, or
+  /// block) with the least unknown slots
+  MostConstrainedUnit,
+}
+
+impl FromStr for SlotSelection {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "first" => Ok(Self::FirstEmpty),
+      "mrv" => Ok(Self::MinimumRemainingValues),
+      "mrv-degree" => Ok(Self::MinimumRemainingValuesDegree),
+      "unit" => Ok(Self::MostConstrainedUnit),
+      _ => Err(format!("Unknown slot selection `{}`, expected one of first, mrv, mrv-degree, unit", s)),
+    }
+  }
+}
+
+/// How a `Strategy` orders the possible answers of a slot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueOrdering {
+  /// In increasing order
+  Natural,
+
+  /// In random order. A deterministic search tries the largest answer first
+  Random,
+
+  /// The answers ruling out the least possible answers of the peers first
+  LeastConstrainingValue,
+}
+
+impl FromStr for ValueOrdering {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "natural" => Ok(Self::Natural),
+      "random" => Ok(Self::Random),
+      "lcv" => Ok(Self::LeastConstrainingValue),
+      _ => Err(format!("Unknown value ordering `{}`, expected one of natural, random, lcv", s)),
+    }
+  }
+}
+
+/// The heuristic made of one of the standard slot selections and value
+/// orderings. Ties between slots are broken at random in a random search.
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let strategy = Strategy::new(SlotSelection::FirstEmpty, ValueOrdering::LeastConstrainingValue);
+/// let ctx = SolvingContext::deterministic().with_heuristic(strategy);
+/// assert_eq!(Board4x4::default().solve_with_ctx(ctx).count(), 288);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Strategy {
+  pub slot_selection: SlotSelection,
+  pub value_ordering: ValueOrdering,
+}
+
+impl Strategy {
+  pub fn new(slot_selection: SlotSelection, value_ordering: ValueOrdering) -> Self {
+    Self { slot_selection, value_ordering }
+  }
+
+  /// The first slot of `slots` minimizing `key`, the order of `slots` being
+  /// shuffled first in a random search
+  fn min_slot_by_key<K: Ord>(mut slots: Vec<Slot>, rng: Option<&mut SolverRng>, key: impl Fn(&Slot) -> K) -> Option<Slot> {
+    if let Some(rng) = rng { slots.shuffle(rng) }
+    let mut result : Option<(K, Slot)> = None;
+    for slot in slots {
+      let k = key(&slot);
+      if result.as_ref().is_none_or(|(best, _)| k < *best) {
+        result = Some((k, slot));
+      }
+    }
+    result.map(|(_, slot)| slot)
+  }
+}
+
+impl Heuristic for Strategy {
+  fn select_slot(&self, node: &dyn SearchNode, rng: Option<&mut SolverRng>) -> Option<Slot> {
+    match self.slot_selection {
+      SlotSelection::FirstEmpty => node.unknown_slots().into_iter().next(),
+      SlotSelection::MinimumRemainingValues => {
+        Self::min_slot_by_key(node.unknown_slots(), rng, |slot| node.num_candidates(slot))
+      },
+      SlotSelection::MinimumRemainingValuesDegree => {
+        Self::min_slot_by_key(node.unknown_slots(), rng, |slot| {
+          let degree = node.peers(slot).iter().filter(|peer| node.get(peer) == 0).count();
+          (node.num_candidates(slot), std::cmp::Reverse(degree))
+        })
+      },
+      SlotSelection::MostConstrainedUnit => {
+        let unit = node.units().into_iter()
+          .map(|unit| unit.into_iter().filter(|slot| node.get(slot) == 0).collect::<Vec<_>>())
+          .filter(|unknowns| !unknowns.is_empty())
+          .min_by_key(|unknowns| unknowns.len())?;
+        Self::min_slot_by_key(unit, rng, |slot| node.num_candidates(slot))
+      },
+    }
+  }
+
+  fn order_values(&self, node: &dyn SearchNode, slot: &Slot, values: &mut Vec<u8>, rng: Option<&mut SolverRng>) {
+    match self.value_ordering {
+      ValueOrdering::Natural => values.sort_unstable(),
+      // The reverse of a shuffle, or of the increasing order, is what the
+      // search has always tried
+      ValueOrdering::Random => {
+        match rng {
+          Some(rng) => values.shuffle(rng),
+          None => values.sort_unstable(),
+        }
+        values.reverse();
+      },
+      ValueOrdering::LeastConstrainingValue => {
+        // Count, for each value, the unknown peers that would lose it
+        let peers = node.peers(slot).into_iter().filter(|peer| node.get(peer) == 0).map(|peer| node.candidates(&peer)).collect::<Vec<_>>();
+        values.sort_by_key(|value| (peers.iter().filter(|candidates| candidates.contains(value)).count(), *value));
+      },
+    }
+  }
+}
@@ -0,0 +1,72 @@
+use std::fmt::Write;
+
+use crate::board::*;
+
+/// The start of a complete LaTeX document holding TikZ grids
+pub const LATEX_DOCUMENT_BEGIN : &str = "\\documentclass{article}\n\\usepackage[margin=2cm]{geometry}\n\\usepackage{tikz}\n\\begin{document}\n";
+
+/// The end of a document started with `LATEX_DOCUMENT_BEGIN`
+pub const LATEX_DOCUMENT_END : &str = "\\end{document}\n";
+
+/// Renders boards as TikZ pictures, to be included in LaTeX documents (with
+/// `\usepackage{tikz}`).
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let board = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]);
+/// let tikz = LatexRenderer::new().render(&board);
+/// assert!(tikz.contains("\\node at (0.5, 3.5) {\\textbf{1}};"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatexRenderer {
+  /// The width of a slot, in centimeters
+  pub cell_size: f64,
+}
+
+impl Default for LatexRenderer {
+  fn default() -> Self {
+    Self { cell_size: 0.6 }
+  }
+}
+
+impl LatexRenderer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn cell_size(mut self, cell_size: f64) -> Self {
+    self.cell_size = cell_size;
+    self
+  }
+
+  /// Render a board, all of its known slots being givens
+  pub fn render<B: Board>(&self, board: &B) -> String {
+    self.render_solution(board, board)
+  }
+
+  /// Render a board being solved from `puzzle`: the givens of `puzzle` are
+  /// set in bold, and the other known slots of `board` in gray. Unknown slots
+  /// are left blank
+  pub fn render_solution<B: Board>(&self, puzzle: &B, board: &B) -> String {
+    let (size, block_size) = (B::size(), B::block_size());
+    let mut s = String::new();
+    writeln!(s, "\\begin{{tikzpicture}}[x={c}cm, y={c}cm]", c = self.cell_size).unwrap();
+    writeln!(s, "  \\draw[step=1, very thin] (0, 0) grid ({n}, {n});", n = size).unwrap();
+    writeln!(s, "  \\draw[step={b}, very thick] (0, 0) grid ({n}, {n});", b = block_size, n = size).unwrap();
+    for slot in B::slots() {
+      let digit : u8 = (*board.get(&slot)).into();
+      if digit == 0 {
+        continue;
+      }
+      // TikZ counts rows from the bottom
+      let (x, y) = (slot.1 as f64 + 0.5, (size - slot.0) as f64 - 0.5);
+      if puzzle.get(&slot).is_answer() {
+        writeln!(s, "  \\node at ({}, {}) {{\\textbf{{{}}}}};", x, y, digit).unwrap();
+      } else {
+        writeln!(s, "  \\node[gray] at ({}, {}) {{{}}};", x, y, digit).unwrap();
+      }
+    }
+    s += "\\end{tikzpicture}";
+    s
+  }
+}
@@ -1,10 +1,47 @@
 mod board;
+mod book;
+mod canonical;
+mod check;
+mod cnf;
+mod difficulty;
+mod explain;
+mod game;
 #[macro_use]
 mod board4x4;
 #[macro_use]
 mod board9x9;
+mod heuristic;
+mod latex;
+mod line;
+mod render;
+mod rng;
+mod sampler;
+#[cfg(feature = "sat")]
+mod sat;
+mod svg;
+mod trace;
+mod transform;
+mod variant;
 pub mod utils;
 
 pub use board::*;
+pub use book::*;
 pub use board4x4::*;
 pub use board9x9::*;
+pub use check::*;
+pub use cnf::*;
+pub use difficulty::*;
+pub use explain::*;
+pub use game::*;
+pub use heuristic::*;
+pub use latex::*;
+pub use line::*;
+pub use render::*;
+pub use rng::*;
+pub use sampler::*;
+#[cfg(feature = "sat")]
+pub use sat::*;
+pub use svg::*;
+pub use trace::*;
+pub use transform::*;
+pub use variant::*;
@@ -0,0 +1,20 @@
+/// Write the digits of a board (row major, `0` for unknown) as a single line,
+/// with `.` for unknown, e.g. an 81 character string for a 9x9 board
+pub fn digits_to_line(digits: &[u8]) -> String {
+  digits.iter().map(|d| if *d == 0 { '.' } else { char::from(b'0' + d) }).collect()
+}
+
+/// Parse a line written by `digits_to_line`, where `0` is accepted for unknown
+/// as well. The line needs to hold a square number of digits from `0` to `9`
+pub fn digits_from_line(line: &str) -> Result<Vec<u8>, String> {
+  let digits = line.trim().chars().map(|c| match c {
+    '.' => Ok(0),
+    '0'..='9' => Ok(c as u8 - b'0'),
+    _ => Err(format!("Invalid character {:?} in board line", c)),
+  }).collect::<Result<Vec<_>, _>>()?;
+  let size = (digits.len() as f64).sqrt() as usize;
+  if size == 0 || size * size != digits.len() {
+    return Err(format!("Board line with {} digits is not a square board", digits.len()));
+  }
+  Ok(digits)
+}
@@ -0,0 +1,168 @@
+use crate::board::*;
+
+const BOLD : &str = "\x1b[1m";
+const DIM : &str = "\x1b[2m";
+const CYAN : &str = "\x1b[36m";
+const RESET : &str = "\x1b[0m";
+
+/// Renders boards as text grids with the block boundaries drawn.
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let board = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]);
+/// assert_eq!(Renderer::ascii().render(&board), "\
+/// +------+------+
+/// | 1  . | .  . |
+/// | .  . | 3  . |
+/// +------+------+
+/// | .  4 | .  . |
+/// | .  . | .  2 |
+/// +------+------+
+/// ");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Renderer {
+  /// Draw the grid with box drawing characters instead of `+`, `-` and `|`
+  pub unicode: bool,
+
+  /// The character shown in unknown slots
+  pub blank: char,
+
+  /// Show the possible answers of every unknown slot as a small grid of
+  /// pencil marks
+  pub candidates: bool,
+
+  /// Use ANSI colors: givens in bold, solved slots in cyan, and pencil marks
+  /// dimmed
+  pub colors: bool,
+}
+
+impl Default for Renderer {
+  fn default() -> Self {
+    Self { unicode: true, blank: '.', candidates: false, colors: false }
+  }
+}
+
+impl Renderer {
+  /// A renderer only using ASCII characters
+  pub fn ascii() -> Self {
+    Self { unicode: false, ..Self::default() }
+  }
+
+  /// A renderer using box drawing characters
+  pub fn unicode() -> Self {
+    Self::default()
+  }
+
+  /// A renderer set up from the `--ascii`, `--candidates` and `--color` flags
+  /// of the binaries
+  pub fn from_flags(ascii: bool, candidates: bool, colors: bool) -> Self {
+    let renderer = if ascii { Self::ascii() } else { Self::unicode() };
+    renderer.candidates(candidates).colors(colors)
+  }
+
+  pub fn blank(mut self, blank: char) -> Self {
+    self.blank = blank;
+    self
+  }
+
+  pub fn candidates(mut self, candidates: bool) -> Self {
+    self.candidates = candidates;
+    self
+  }
+
+  pub fn colors(mut self, colors: bool) -> Self {
+    self.colors = colors;
+    self
+  }
+
+  /// Render a board, all of its known slots being givens
+  pub fn render<B: Board>(&self, board: &B) -> String {
+    self.render_solution(board, board)
+  }
+
+  /// Render a board being solved from `puzzle`: the known slots of `puzzle`
+  /// are the givens, and the other known slots of `board` are solved slots
+  pub fn render_solution<B: Board>(&self, puzzle: &B, board: &B) -> String {
+    let (size, block_size) = (B::size(), B::block_size());
+    let digit_width = size.to_string().len();
+
+    // Every slot is a `height` by `width` box of characters
+    let (width, height) = if self.candidates {
+      (block_size * (digit_width + 1) + 1, block_size)
+    } else {
+      (digit_width + 2, 1)
+    };
+    let border = |left: &str, middle: &str, right: &str| {
+      let segment = if self.unicode { "─" } else { "-" }.repeat(width * block_size);
+      format!("{}{}{}\n", left, vec![segment; block_size].join(middle), right)
+    };
+    let (top, middle, bottom, vertical) = if self.unicode {
+      (border("┌", "┬", "┐"), border("├", "┼", "┤"), border("└", "┴", "┘"), "│")
+    } else {
+      (border("+", "+", "+"), border("+", "+", "+"), border("+", "+", "+"), "|")
+    };
+
+    let mut s = top;
+    for i in 0..size {
+      if i > 0 && i % block_size == 0 {
+        s += &middle;
+      } else if i > 0 && self.candidates {
+        // Leave room between the pencil marks of two slots
+        s += &self.blank_line(vertical, width, block_size);
+      }
+      for line in 0..height {
+        s += vertical;
+        for j in 0..size {
+          s += &self.slot_line(puzzle, board, &(i, j), line, width, digit_width);
+          if (j + 1) % block_size == 0 {
+            s += vertical;
+          }
+        }
+        s += "\n";
+      }
+    }
+    s += &bottom;
+    s
+  }
+
+  fn blank_line(&self, vertical: &str, width: usize, block_size: usize) -> String {
+    let block = " ".repeat(width * block_size);
+    format!("{}{}{}\n", vertical, vec![block; block_size].join(vertical), vertical)
+  }
+
+  /// One line of the box of a slot
+  fn slot_line<B: Board>(&self, puzzle: &B, board: &B, slot: &Slot, line: usize, width: usize, digit_width: usize) -> String {
+    let block_size = B::block_size();
+    let digit : u8 = (*board.get(slot)).into();
+    let center = |text: String, len: usize, style: &str| {
+      let left = (width - len) / 2;
+      let right = width - len - left;
+      if self.colors && !style.is_empty() {
+        format!("{}{}{}{}{}", " ".repeat(left), style, text, RESET, " ".repeat(right))
+      } else {
+        format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+      }
+    };
+
+    if digit != 0 {
+      // Known slots show their digit on the middle line
+      if line != block_size / 2 && self.candidates {
+        return " ".repeat(width);
+      }
+      let style = if puzzle.get(slot).is_answer() { BOLD } else { CYAN };
+      let text = format!("{:>w$}", digit, w = digit_width);
+      let (text, len) = if self.candidates { (format!("[{}]", text), digit_width + 2) } else { (text, digit_width) };
+      center(text, len, style)
+    } else if self.candidates {
+      let answers = board.possible_answers(slot).elements().into_iter().map(|e| e.into()).collect::<Vec<u8>>();
+      let marks = (0..block_size).map(|k| {
+        let d = (line * block_size + k + 1) as u8;
+        if answers.contains(&d) { format!("{:>w$}", d, w = digit_width) } else { " ".repeat(digit_width) }
+      }).collect::<Vec<_>>().join(" ");
+      center(marks, block_size * (digit_width + 1) - 1, DIM)
+    } else {
+      center(format!("{:>w$}", self.blank, w = digit_width), digit_width, "")
+    }
+  }
+}
@@ -0,0 +1,80 @@
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde_json::{json, Value};
+
+/// The random number generator used by the solver.
+///
+/// It is a ChaCha20 generator that remembers its seed, so that its state (the
+/// seed and the position in the stream) can be saved in a checkpoint and
+/// restored later on, producing exactly the same numbers.
+#[derive(Clone, Debug)]
+pub struct SolverRng {
+  seed: [u8; 32],
+  rng: ChaCha20Rng,
+  /// Number of 32-bit words consumed from the stream so far
+  word_pos: u128,
+}
+
+impl SolverRng {
+  pub fn from_entropy() -> Self {
+    let mut seed = [0; 32];
+    rand::rngs::OsRng.fill_bytes(&mut seed);
+    Self::from_seed(seed)
+  }
+
+  pub fn seed_from_u64(seed: u64) -> Self {
+    let mut bytes = [0; 32];
+    ChaCha20Rng::seed_from_u64(seed).fill_bytes(&mut bytes);
+    Self::from_seed(bytes)
+  }
+
+  pub fn from_seed(seed: [u8; 32]) -> Self {
+    Self { seed, rng: ChaCha20Rng::from_seed(seed), word_pos: 0 }
+  }
+
+  /// Save the state of the generator into json
+  pub fn to_json(&self) -> Value {
+    let seed = self.seed.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    json!({ "seed": seed, "word_pos": self.word_pos.to_string() })
+  }
+
+  /// Restore a generator saved with `to_json`
+  pub fn from_json(value: &Value) -> Result<Self, String> {
+    let seed_str = value["seed"].as_str().ok_or("Missing rng seed")?;
+    if seed_str.len() != 64 {
+      return Err("Invalid rng seed".to_string());
+    }
+    let mut seed = [0; 32];
+    for (i, byte) in seed.iter_mut().enumerate() {
+      *byte = u8::from_str_radix(&seed_str[i * 2..i * 2 + 2], 16).map_err(|_| "Invalid rng seed")?;
+    }
+    let word_pos = value["word_pos"].as_str().and_then(|pos| pos.parse::<u128>().ok()).ok_or("Invalid rng position")?;
+    let mut result = Self::from_seed(seed);
+    result.rng.set_word_pos(word_pos);
+    result.word_pos = word_pos;
+    Ok(result)
+  }
+}
+
+impl RngCore for SolverRng {
+  fn next_u32(&mut self) -> u32 {
+    self.word_pos += 1;
+    self.rng.next_u32()
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.word_pos += 2;
+    self.rng.next_u64()
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    // Bytes are taken from whole words, the rest of the last word is dropped
+    self.word_pos += (dest.len() as u128).div_ceil(4);
+    self.rng.fill_bytes(dest)
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+    self.word_pos += (dest.len() as u128).div_ceil(4);
+    self.rng.try_fill_bytes(dest)
+  }
+}
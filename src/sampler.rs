@@ -0,0 +1,170 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rand::seq::SliceRandom;
+
+use crate::board::*;
+
+/// Samples complete grids (approximately) uniformly at random.
+///
+/// `BoardSolutions` with a random context is biased towards the grids that
+/// are easier to reach in its search. This sampler is a Markov chain whose
+/// stationary distribution is the uniform distribution over all complete
+/// grids, so the samples get arbitrarily close to uniform as the number of
+/// steps between two samples grows.
+///
+/// Each step of the chain is made of two moves that both keep the uniform
+/// distribution invariant:
+///
+/// - An independence Metropolis-Hastings move. A grid is proposed by filling
+///   the most constrained slot with a uniformly chosen possible answer until
+///   the board is full (restarting on dead ends), so the probability of
+///   proposing a grid is proportional to `1 / weight`, where `weight` is the
+///   product of the number of possible answers at every choice. The proposal
+///   is accepted with probability `min(1, weight(proposal) / weight(current))`.
+/// - Applying a uniformly random symmetry transform to the current grid.
+///
+/// ```
+/// # use sudoku_rs::*;
+/// for grid in UniformSampler::<Board9x9>::with_seed(1).take(2) {
+///   assert!(!grid.has_unknown_slot());
+/// }
+/// ```
+pub struct UniformSampler<B> where B : Board {
+  rng: StdRng,
+  current: Option<(B, f64)>,
+  burn_in: usize,
+  steps_per_sample: usize,
+  num_proposals: usize,
+  num_accepted: usize,
+}
+
+impl<B> UniformSampler<B> where B : Board {
+  /// Default number of steps being taken before the first sample
+  pub const DEFAULT_BURN_IN : usize = 100;
+
+  /// Default number of steps being taken between two samples
+  pub const DEFAULT_STEPS_PER_SAMPLE : usize = 20;
+
+  pub fn new() -> Self {
+    Self::with_rng(StdRng::from_entropy())
+  }
+
+  pub fn with_seed(seed: u64) -> Self {
+    Self::with_rng(StdRng::seed_from_u64(seed))
+  }
+
+  pub fn with_rng(rng: StdRng) -> Self {
+    Self {
+      rng,
+      current: None,
+      burn_in: Self::DEFAULT_BURN_IN,
+      steps_per_sample: Self::DEFAULT_STEPS_PER_SAMPLE,
+      num_proposals: 0,
+      num_accepted: 0,
+    }
+  }
+
+  /// Set the number of steps being taken before the first sample
+  pub fn burn_in(mut self, steps: usize) -> Self {
+    self.burn_in = steps;
+    self
+  }
+
+  /// Set the number of steps being taken between two samples. More steps
+  /// make consecutive samples less correlated
+  pub fn steps_per_sample(mut self, steps: usize) -> Self {
+    self.steps_per_sample = steps.max(1);
+    self
+  }
+
+  /// The ratio of accepted proposals so far
+  pub fn acceptance_rate(&self) -> f64 {
+    if self.num_proposals == 0 { 0.0 } else { self.num_accepted as f64 / self.num_proposals as f64 }
+  }
+
+  /// Take one step of the chain
+  fn step(&mut self) {
+    let (proposal, proposal_weight) = self.propose();
+    self.num_proposals += 1;
+    let accept = match &self.current {
+      Some((_, current_weight)) => {
+        let ratio = (proposal_weight - current_weight).exp();
+        ratio >= 1.0 || self.rng.gen::<f64>() < ratio
+      },
+      None => true,
+    };
+    if accept {
+      self.num_accepted += 1;
+      self.current = Some((proposal, proposal_weight));
+    }
+
+    // Move to a random isomorph, whose weight has to be computed again
+    if let Some((grid, _)) = &self.current {
+      let (grid, _) = grid.random_isomorph_with_rng(&mut self.rng);
+      let weight = Self::log_weight(&grid);
+      self.current = Some((grid, weight));
+    }
+  }
+
+  /// Propose a full grid, along with the logarithm of its weight
+  fn propose(&mut self) -> (B, f64) {
+    loop {
+      let mut board = B::default();
+      let mut log_weight = 0.0;
+      loop {
+        match Self::most_constrained_slot(&board) {
+          None => return (board, log_weight),
+          Some((slot, answers)) => {
+            let answers = answers.elements();
+            if answers.is_empty() {
+              break;
+            }
+            log_weight += (answers.len() as f64).ln();
+            board.set(&slot, *answers.choose(&mut self.rng).unwrap());
+          }
+        }
+      }
+    }
+  }
+
+  /// The logarithm of the weight of a full grid, obtained by replaying the
+  /// choices that `propose` would make to reach it
+  fn log_weight(grid: &B) -> f64 {
+    let mut board = B::default();
+    let mut log_weight = 0.0;
+    while let Some((slot, answers)) = Self::most_constrained_slot(&board) {
+      log_weight += (answers.count() as f64).ln();
+      board.set(&slot, *grid.get(&slot));
+    }
+    log_weight
+  }
+
+  /// The first unknown slot (in row major order) with the least possible answers
+  fn most_constrained_slot(board: &B) -> Option<(Slot, B::ElementSet)> {
+    let mut result : Option<(Slot, B::ElementSet)> = None;
+    for slot in board.unknown_slots() {
+      let answers = board.possible_answers(&slot);
+      if result.is_none_or(|(_, best)| answers.count() < best.count()) {
+        result = Some((slot, answers));
+      }
+    }
+    result
+  }
+}
+
+impl<B> Default for UniformSampler<B> where B : Board {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<B> Iterator for UniformSampler<B> where B : Board {
+  type Item = B;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let steps = if self.current.is_none() { self.burn_in.max(1) } else { self.steps_per_sample };
+    for _ in 0..steps {
+      self.step();
+    }
+    self.current.as_ref().map(|(grid, _)| grid.clone())
+  }
+}
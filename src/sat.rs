@@ -0,0 +1,400 @@
+use rand::Rng;
+
+use crate::board::BudgetExhausted;
+use crate::cnf::Cnf;
+
+/// A literal: variable `var` (from `0`) is `2 * var` when positive and
+/// `2 * var + 1` when negative
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Lit(usize);
+
+impl Lit {
+  fn from_dimacs(lit: i32) -> Self {
+    let var = lit.unsigned_abs() as usize - 1;
+    Self(2 * var + (lit < 0) as usize)
+  }
+
+  fn var(self) -> usize {
+    self.0 / 2
+  }
+
+  fn is_negative(self) -> bool {
+    self.0 % 2 == 1
+  }
+
+  fn neg(self) -> Self {
+    Self(self.0 ^ 1)
+  }
+}
+
+/// Counters of the work done by a `SatSolver`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SatStats {
+  pub decisions: usize,
+  pub conflicts: usize,
+  pub propagations: usize,
+  pub max_level: usize,
+}
+
+/// A small CDCL (conflict driven clause learning) SAT solver, with two watched
+/// literals, first UIP clause learning, activity based branching with phase
+/// saving, and Luby restarts.
+///
+/// Clauses can be added between two calls to `solve`, which is how solutions
+/// are enumerated: each model is blocked by a clause before solving again.
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let mut solver = SatSolver::new(2);
+/// solver.add_clause(&[1, 2]);
+/// solver.add_clause(&[-1]);
+/// assert_eq!(solver.solve(), Some(vec![-1, 2]));
+/// solver.add_clause(&[-2]);
+/// assert_eq!(solver.solve(), None);
+/// ```
+pub struct SatSolver {
+  clauses: Vec<Vec<Lit>>,
+  /// `watches[lit]` are the clauses watching `lit`, i.e. to be visited when
+  /// `lit` becomes false
+  watches: Vec<Vec<usize>>,
+  /// `1` for true, `-1` for false, `0` for unassigned
+  values: Vec<i8>,
+  level: Vec<usize>,
+  reason: Vec<Option<usize>>,
+  trail: Vec<Lit>,
+  trail_lim: Vec<usize>,
+  qhead: usize,
+  activity: Vec<f64>,
+  var_inc: f64,
+  /// The last value of every variable, reused when branching on it
+  polarity: Vec<bool>,
+  seen: Vec<bool>,
+  unsat: bool,
+  stats: SatStats,
+}
+
+impl SatSolver {
+  const RESTART_BASE : usize = 100;
+  const ACTIVITY_DECAY : f64 = 0.95;
+
+  pub fn new(num_variables: usize) -> Self {
+    Self {
+      clauses: vec![],
+      watches: vec![vec![]; 2 * num_variables],
+      values: vec![0; num_variables],
+      level: vec![0; num_variables],
+      reason: vec![None; num_variables],
+      trail: vec![],
+      trail_lim: vec![],
+      qhead: 0,
+      activity: vec![0.0; num_variables],
+      var_inc: 1.0,
+      polarity: vec![false; num_variables],
+      seen: vec![false; num_variables],
+      unsat: false,
+      stats: SatStats::default(),
+    }
+  }
+
+  /// Load all the clauses of a formula
+  pub fn from_cnf(cnf: &Cnf) -> Self {
+    let mut solver = Self::new(cnf.num_variables);
+    for clause in &cnf.clauses {
+      solver.add_clause(clause);
+    }
+    solver
+  }
+
+  pub fn num_variables(&self) -> usize {
+    self.values.len()
+  }
+
+  pub fn stats(&self) -> SatStats {
+    self.stats
+  }
+
+  /// Randomize the initial phases and branching order, so that different
+  /// generators lead to different models
+  pub fn randomize<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+    for var in 0..self.num_variables() {
+      self.polarity[var] = rng.gen();
+      self.activity[var] += rng.gen::<f64>() * 1e-3;
+    }
+  }
+
+  /// Add a clause, given in DIMACS literals
+  pub fn add_clause(&mut self, clause: &[i32]) {
+    self.cancel_until(0);
+    if self.unsat {
+      return;
+    }
+    let mut lits = Vec::with_capacity(clause.len());
+    for lit in clause.iter().map(|lit| Lit::from_dimacs(*lit)) {
+      match self.value(lit) {
+        1 => return,
+        -1 => {},
+        _ => if lits.contains(&lit.neg()) { return } else if !lits.contains(&lit) { lits.push(lit) },
+      }
+    }
+    match lits.len() {
+      0 => self.unsat = true,
+      1 => {
+        self.enqueue(lits[0], None);
+        if self.propagate().is_some() {
+          self.unsat = true;
+        }
+      },
+      _ => { self.attach(lits); },
+    }
+  }
+
+  /// Find a model, returned as the value of every variable in DIMACS
+  /// literals. Returns `None` when the formula is unsatisfiable
+  pub fn solve(&mut self) -> Option<Vec<i32>> {
+    self.solve_with_budget(|_| Ok(())).unwrap_or(None)
+  }
+
+  /// Find a model, calling `check` with the number of decisions made so far
+  /// before every decision. The search stops as soon as `check` fails, and can
+  /// be continued by calling `solve_with_budget` again
+  pub fn solve_with_budget<F>(&mut self, check: F) -> Result<Option<Vec<i32>>, BudgetExhausted>
+    where F : Fn(usize) -> Result<(), BudgetExhausted>
+  {
+    if self.unsat {
+      return Ok(None);
+    }
+    let mut restart = 0;
+    let mut conflicts_left = Self::RESTART_BASE * luby(restart);
+    loop {
+      if let Some(conflict) = self.propagate() {
+        self.stats.conflicts += 1;
+        if self.trail_lim.is_empty() {
+          self.unsat = true;
+          return Ok(None);
+        }
+        let (learnt, level) = self.analyze(conflict);
+        self.cancel_until(level);
+        let asserting = learnt[0];
+        if learnt.len() == 1 {
+          self.enqueue(asserting, None);
+        } else {
+          let index = self.attach(learnt);
+          self.enqueue(asserting, Some(index));
+        }
+        self.var_inc /= Self::ACTIVITY_DECAY;
+
+        conflicts_left -= 1;
+        if conflicts_left == 0 {
+          restart += 1;
+          conflicts_left = Self::RESTART_BASE * luby(restart);
+          self.cancel_until(0);
+        }
+      } else {
+        if let Err(reason) = check(self.stats.decisions) {
+          self.cancel_until(0);
+          return Err(reason);
+        }
+        match self.pick_branch() {
+          Some(lit) => {
+            self.stats.decisions += 1;
+            self.trail_lim.push(self.trail.len());
+            self.stats.max_level = self.stats.max_level.max(self.trail_lim.len());
+            self.enqueue(lit, None);
+          },
+          None => {
+            let model = (0..self.num_variables()).map(|var| {
+              let lit = (var + 1) as i32;
+              if self.values[var] > 0 { lit } else { -lit }
+            }).collect();
+            self.cancel_until(0);
+            return Ok(Some(model));
+          }
+        }
+      }
+    }
+  }
+
+  fn value(&self, lit: Lit) -> i8 {
+    let value = self.values[lit.var()];
+    if lit.is_negative() { -value } else { value }
+  }
+
+  /// Add a clause of at least two literals, watching its first two literals
+  fn attach(&mut self, lits: Vec<Lit>) -> usize {
+    let index = self.clauses.len();
+    self.watches[lits[0].0].push(index);
+    self.watches[lits[1].0].push(index);
+    self.clauses.push(lits);
+    index
+  }
+
+  fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+    let var = lit.var();
+    self.values[var] = if lit.is_negative() { -1 } else { 1 };
+    self.level[var] = self.trail_lim.len();
+    self.reason[var] = reason;
+    self.trail.push(lit);
+  }
+
+  /// Propagate all the assignments on the trail. Returns the conflicting
+  /// clause, if any. The literal implied by a clause is always its first one
+  fn propagate(&mut self) -> Option<usize> {
+    while self.qhead < self.trail.len() {
+      let false_lit = self.trail[self.qhead].neg();
+      self.qhead += 1;
+      let mut watchers = std::mem::take(&mut self.watches[false_lit.0]);
+      let (mut i, mut j) = (0, 0);
+      let mut conflict = None;
+      while i < watchers.len() {
+        let index = watchers[i];
+        i += 1;
+
+        // Make sure the false literal is the second one
+        let clause = &mut self.clauses[index];
+        if clause[0] == false_lit {
+          clause.swap(0, 1);
+        }
+        let first = clause[0];
+        if self.values[first.var()] != 0 && (self.values[first.var()] < 0) == first.is_negative() {
+          watchers[j] = index;
+          j += 1;
+          continue;
+        }
+
+        // Look for another literal to watch
+        let values = &self.values;
+        let replacement = (2..clause.len()).find(|k| {
+          let lit = clause[*k];
+          values[lit.var()] == 0 || (values[lit.var()] < 0) == lit.is_negative()
+        });
+        if let Some(k) = replacement {
+          clause.swap(1, k);
+          self.watches[clause[1].0].push(index);
+          continue;
+        }
+
+        // The clause is unit or conflicting
+        watchers[j] = index;
+        j += 1;
+        if self.value(first) == -1 {
+          conflict = Some(index);
+          while i < watchers.len() {
+            watchers[j] = watchers[i];
+            i += 1;
+            j += 1;
+          }
+        } else {
+          self.stats.propagations += 1;
+          self.enqueue(first, Some(index));
+        }
+      }
+      watchers.truncate(j);
+      self.watches[false_lit.0] = watchers;
+      if conflict.is_some() {
+        self.qhead = self.trail.len();
+        return conflict;
+      }
+    }
+    None
+  }
+
+  /// Learn the first UIP clause of a conflict. Returns the clause, whose first
+  /// literal is the asserting one, and the level to backjump to
+  fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+    let current_level = self.trail_lim.len();
+    let mut learnt = vec![Lit(0)];
+    let mut pending = 0;
+    let mut index = self.trail.len();
+    let mut clause_index = conflict;
+    let mut skip_first = false;
+    let asserting = loop {
+      for k in skip_first as usize..self.clauses[clause_index].len() {
+        let lit = self.clauses[clause_index][k];
+        let var = lit.var();
+        if !self.seen[var] && self.level[var] > 0 {
+          self.bump(var);
+          self.seen[var] = true;
+          if self.level[var] >= current_level {
+            pending += 1;
+          } else {
+            learnt.push(lit);
+          }
+        }
+      }
+
+      // Go back to the last seen literal of the trail
+      loop {
+        index -= 1;
+        if self.seen[self.trail[index].var()] { break }
+      }
+      let lit = self.trail[index];
+      self.seen[lit.var()] = false;
+      pending -= 1;
+      if pending == 0 {
+        break lit;
+      }
+      clause_index = self.reason[lit.var()].expect("Only decisions have no reason");
+      skip_first = true;
+    };
+    learnt[0] = asserting.neg();
+    for lit in &learnt[1..] {
+      self.seen[lit.var()] = false;
+    }
+
+    // Backjump to the second highest level, whose literal is watched
+    let mut level = 0;
+    if learnt.len() > 1 {
+      let max = (1..learnt.len()).max_by_key(|k| self.level[learnt[*k].var()]).unwrap();
+      learnt.swap(1, max);
+      level = self.level[learnt[1].var()];
+    }
+    (learnt, level)
+  }
+
+  fn bump(&mut self, var: usize) {
+    self.activity[var] += self.var_inc;
+    if self.activity[var] > 1e100 {
+      for activity in self.activity.iter_mut() {
+        *activity *= 1e-100;
+      }
+      self.var_inc *= 1e-100;
+    }
+  }
+
+  fn cancel_until(&mut self, level: usize) {
+    if self.trail_lim.len() > level {
+      for lit in self.trail.drain(self.trail_lim[level]..) {
+        let var = lit.var();
+        self.polarity[var] = !lit.is_negative();
+        self.values[var] = 0;
+        self.reason[var] = None;
+      }
+      self.trail_lim.truncate(level);
+      self.qhead = self.trail.len();
+    }
+  }
+
+  /// The unassigned variable with the highest activity, with its saved phase
+  fn pick_branch(&self) -> Option<Lit> {
+    let var = (0..self.num_variables())
+      .filter(|var| self.values[*var] == 0)
+      .max_by(|a, b| self.activity[*a].total_cmp(&self.activity[*b]).then(b.cmp(a)))?;
+    Some(Lit(2 * var + !self.polarity[var] as usize))
+  }
+}
+
+/// The Luby sequence 1, 1, 2, 1, 1, 2, 4, 1, ... (starting from index `0`)
+fn luby(mut i: usize) -> usize {
+  let mut size = 1;
+  let mut seq = 0;
+  while size < i + 1 {
+    seq += 1;
+    size = 2 * size + 1;
+  }
+  while size - 1 != i {
+    size = (size - 1) / 2;
+    seq -= 1;
+    i %= size;
+  }
+  1 << seq
+}
@@ -0,0 +1,166 @@
+use std::fmt::Write;
+
+use crate::board::*;
+
+/// Variant constraints drawn on top of the grid
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decoration {
+  /// The two main diagonals, as in diagonal (X) sudoku
+  Diagonals,
+
+  /// A killer cage: a dashed outline around its slots, with its sum (if any)
+  /// in the corner of the first slot
+  Cage { slots: Vec<Slot>, sum: Option<u32> },
+}
+
+/// Renders boards as SVG images, e.g. for printing.
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let board = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]);
+/// let svg = SvgRenderer::new().decoration(Decoration::Diagonals).render(&board);
+/// assert!(svg.starts_with("<svg"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgRenderer {
+  /// The width of a slot, in pixels
+  pub cell_size: f64,
+
+  /// Show the possible answers of every unknown slot as small pencil marks
+  pub candidates: bool,
+
+  /// The variant constraints to draw
+  pub decorations: Vec<Decoration>,
+}
+
+impl Default for SvgRenderer {
+  fn default() -> Self {
+    Self { cell_size: 40.0, candidates: false, decorations: vec![] }
+  }
+}
+
+/// The color of the digits filled in by solving, givens being black
+const SOLVED_COLOR : &str = "#1a5fb4";
+const CANDIDATE_COLOR : &str = "#777777";
+const DECORATION_COLOR : &str = "#c01c28";
+
+impl SvgRenderer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn cell_size(mut self, cell_size: f64) -> Self {
+    self.cell_size = cell_size;
+    self
+  }
+
+  pub fn candidates(mut self, candidates: bool) -> Self {
+    self.candidates = candidates;
+    self
+  }
+
+  pub fn decoration(mut self, decoration: Decoration) -> Self {
+    self.decorations.push(decoration);
+    self
+  }
+
+  /// Render a board, all of its known slots being givens
+  pub fn render<B: Board>(&self, board: &B) -> String {
+    self.render_solution(board, board)
+  }
+
+  /// Render a board being solved from `puzzle`: the givens of `puzzle` are
+  /// drawn in bold black, and the other known slots of `board` in blue
+  pub fn render_solution<B: Board>(&self, puzzle: &B, board: &B) -> String {
+    let (size, block_size) = (B::size(), B::block_size());
+    let cell = self.cell_size;
+    let margin = (cell / 10.0).ceil();
+    let width = cell * size as f64 + 2.0 * margin;
+
+    let mut s = String::new();
+    writeln!(s, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{w}" viewBox="0 0 {w} {w}">"#, w = width).unwrap();
+    writeln!(s, r#"<rect width="{w}" height="{w}" fill="white"/>"#, w = width).unwrap();
+    writeln!(s, r#"<g transform="translate({m},{m})" font-family="sans-serif" text-anchor="middle" dominant-baseline="central">"#, m = margin).unwrap();
+
+    for decoration in &self.decorations {
+      self.write_decoration::<B>(&mut s, decoration);
+    }
+
+    // Thin lines between slots, thick lines between blocks
+    for k in 0..=size {
+      let x = cell * k as f64;
+      let stroke_width = if k % block_size == 0 { cell / 16.0 } else { cell / 40.0 };
+      let end = cell * size as f64;
+      writeln!(s, r#"<line x1="{x}" y1="0" x2="{x}" y2="{end}" stroke="black" stroke-width="{sw}" stroke-linecap="square"/>"#, x = x, end = end, sw = stroke_width).unwrap();
+      writeln!(s, r#"<line x1="0" y1="{x}" x2="{end}" y2="{x}" stroke="black" stroke-width="{sw}" stroke-linecap="square"/>"#, x = x, end = end, sw = stroke_width).unwrap();
+    }
+
+    for slot in B::slots() {
+      let (x, y) = (cell * slot.1 as f64, cell * slot.0 as f64);
+      let digit : u8 = (*board.get(&slot)).into();
+      if digit != 0 {
+        let style = if puzzle.get(&slot).is_answer() {
+          r#"fill="black" font-weight="bold""#.to_string()
+        } else {
+          format!(r#"fill="{}""#, SOLVED_COLOR)
+        };
+        writeln!(s, r#"<text x="{}" y="{}" font-size="{}" {}>{}</text>"#, x + cell / 2.0, y + cell / 2.0, cell * 0.6, style, digit).unwrap();
+      } else if self.candidates {
+        // Pencil marks are laid out like the digits of a block
+        let sub = cell / block_size as f64;
+        for answer in board.possible_answers(&slot).elements() {
+          let d : u8 = answer.into();
+          let d = d as usize - 1;
+          let (cx, cy) = (x + sub * ((d % block_size) as f64 + 0.5), y + sub * ((d / block_size) as f64 + 0.5));
+          writeln!(s, r#"<text x="{}" y="{}" font-size="{}" fill="{}">{}</text>"#, cx, cy, sub * 0.7, CANDIDATE_COLOR, d + 1).unwrap();
+        }
+      }
+    }
+
+    s += "</g>\n</svg>\n";
+    s
+  }
+
+  fn write_decoration<B: Board>(&self, s: &mut String, decoration: &Decoration) {
+    let cell = self.cell_size;
+    let end = cell * B::size() as f64;
+    match decoration {
+      Decoration::Diagonals => {
+        writeln!(s, r#"<line x1="0" y1="0" x2="{e}" y2="{e}" stroke="{c}" stroke-width="{sw}" stroke-opacity="0.5"/>"#, e = end, c = DECORATION_COLOR, sw = cell / 20.0).unwrap();
+        writeln!(s, r#"<line x1="0" y1="{e}" x2="{e}" y2="0" stroke="{c}" stroke-width="{sw}" stroke-opacity="0.5"/>"#, e = end, c = DECORATION_COLOR, sw = cell / 20.0).unwrap();
+      },
+      Decoration::Cage { slots, sum } => {
+        // Dashed sides, inset into the slots, wherever the neighbour is not
+        // part of the cage. Sides are extended towards the neighbours in the
+        // cage so that the outline stays connected
+        let inset = cell / 10.0;
+        let contains = |i: isize, j: isize| i >= 0 && j >= 0 && slots.contains(&(i as usize, j as usize));
+        for &(i, j) in slots {
+          let (i, j) = (i as isize, j as isize);
+          let (up, down, left, right) = (contains(i - 1, j), contains(i + 1, j), contains(i, j - 1), contains(i, j + 1));
+          let (x0, y0) = (cell * j as f64, cell * i as f64);
+          let (x1, y1) = (x0 + cell, y0 + cell);
+          let top = if up { y0 } else { y0 + inset };
+          let bottom = if down { y1 } else { y1 - inset };
+          let start = if left { x0 } else { x0 + inset };
+          let end = if right { x1 } else { x1 - inset };
+          let sides = [
+            (up, (start, y0 + inset, end, y0 + inset)),
+            (down, (start, y1 - inset, end, y1 - inset)),
+            (left, (x0 + inset, top, x0 + inset, bottom)),
+            (right, (x1 - inset, top, x1 - inset, bottom)),
+          ];
+          for (shared, (ax, ay, bx, by)) in sides.iter() {
+            if !shared {
+              writeln!(s, r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-dasharray="{}"/>"#, ax, ay, bx, by, DECORATION_COLOR, cell / 40.0, cell / 10.0).unwrap();
+            }
+          }
+        }
+        if let (Some(sum), Some(&(i, j))) = (sum, slots.iter().min()) {
+          let (x, y) = (cell * j as f64 + inset * 1.5, cell * i as f64 + inset * 1.5);
+          writeln!(s, r#"<text x="{}" y="{}" font-size="{}" fill="{}" text-anchor="start" dominant-baseline="hanging" stroke="white" stroke-width="{}" paint-order="stroke">{}</text>"#, x, y, cell / 4.0, DECORATION_COLOR, cell / 20.0, sum).unwrap();
+        }
+      },
+    }
+  }
+}
@@ -0,0 +1,202 @@
+use crate::board::*;
+use crate::heuristic::*;
+
+/// Why a digit was placed during a traced solve
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StepReason {
+  /// The digit is given by the question
+  Given,
+
+  /// The slot has only one possible answer left
+  NakedSingle,
+
+  /// The slot is the only place left for the digit inside a row, a column, or
+  /// a block
+  HiddenSingle,
+
+  /// The digit is one of the possible answers of the slot branched on
+  Guess,
+
+  /// The guess of the digit led to a contradiction (or, when solving towards
+  /// a target, to another solution), and is taken back along with everything
+  /// placed after it
+  Backtrack,
+}
+
+impl StepReason {
+  pub fn all() -> [StepReason; 5] {
+    [StepReason::Given, StepReason::NakedSingle, StepReason::HiddenSingle, StepReason::Guess, StepReason::Backtrack]
+  }
+}
+
+impl std::fmt::Display for StepReason {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let name = match self {
+      StepReason::Given => "given",
+      StepReason::NakedSingle => "naked_single",
+      StepReason::HiddenSingle => "hidden_single",
+      StepReason::Guess => "guess",
+      StepReason::Backtrack => "backtrack",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+impl std::str::FromStr for StepReason {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    StepReason::all().iter().copied().find(|r| r.to_string() == s).ok_or_else(|| format!("Unknown step reason {}", s))
+  }
+}
+
+/// A placement of a digit in a slot, or the undoing of a guess
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceStep {
+  pub slot: Slot,
+  pub value: u8,
+  pub reason: StepReason,
+}
+
+impl std::fmt::Display for TraceStep {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let (i, j) = self.slot;
+    write!(f, "{} ({}, {}) = {}", self.reason, i, j, self.value)
+  }
+}
+
+/// The steps taken by the solver to solve a question. See
+/// `Board::solve_with_trace`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveTrace<B> where B : Board {
+  pub steps: Vec<TraceStep>,
+
+  /// The solution reached at the end of the steps, if any
+  pub solution: Option<B>,
+}
+
+impl<B> SolveTrace<B> where B : Board {
+  /// The board after every step, starting from an empty board. A backtrack
+  /// step brings back the board as it was before the guess taken back
+  pub fn boards(&self) -> Vec<B> {
+    let mut board = B::default();
+    let mut saved = vec![];
+    self.steps.iter().map(|step| {
+      match step.reason {
+        StepReason::Backtrack => board = saved.pop().expect("Backtracks follow their guess"),
+        reason => {
+          if reason == StepReason::Guess {
+            saved.push(board.clone());
+          }
+          board.set(&step.slot, B::Element::from(step.value));
+        },
+      }
+      board.clone()
+    }).collect()
+  }
+
+  /// Number of guesses made, including the ones taken back
+  pub fn guesses(&self) -> usize {
+    self.steps.iter().filter(|step| step.reason == StepReason::Guess).count()
+  }
+}
+
+/// Algorithm: a depth first search with the slot selection and value ordering
+/// of `SolvingContext::deterministic`, placing singles one at a time so that
+/// every step is justified by the board right before it. With a target, a
+/// guess leading to another solution is taken back like a contradiction, so
+/// the search ends at the target
+pub(crate) fn trace<B: Board>(board: &B, target: Option<&B>) -> SolveTrace<B> {
+  let mut steps = B::slots()
+    .filter(|slot| board.get(slot).is_answer())
+    .map(|slot| TraceStep { slot, value: (*board.get(&slot)).into(), reason: StepReason::Given })
+    .collect::<Vec<_>>();
+  let solution = if board.conflicts().is_empty() {
+    let strategy = Strategy::new(SlotSelection::MinimumRemainingValues, ValueOrdering::Random);
+    search(board.clone(), &strategy, target, true, &mut steps).ok().flatten()
+  } else {
+    None
+  };
+  SolveTrace { steps, solution }
+}
+
+/// A solution other than the target was reached, so the search goes back to
+/// the guess leaving the way to the target
+struct Diverged;
+
+/// `on_target` tells if the target can still be reached from `board`
+fn search<B: Board>(mut board: B, strategy: &Strategy, target: Option<&B>, on_target: bool, steps: &mut Vec<TraceStep>) -> Result<Option<B>, Diverged> {
+  loop {
+    match next_single(&board) {
+      Some(Some(step)) => {
+        board.set(&step.slot, B::Element::from(step.value));
+        steps.push(step);
+      },
+      Some(None) => break,
+      None => return Ok(None),
+    }
+  }
+
+  let node = BoardNode(&board);
+  let slot = match strategy.select_slot(&node, None) {
+    Some(slot) => slot,
+    None if target.is_none_or(|target| *target == board) => return Ok(Some(board)),
+    None => return Err(Diverged),
+  };
+  let mut values = node.candidates(&slot);
+  strategy.order_values(&node, &slot, &mut values, None);
+  for value in values {
+    steps.push(TraceStep { slot, value, reason: StepReason::Guess });
+    let mut guessed = board.clone();
+    guessed.set(&slot, B::Element::from(value));
+    let guess_on_target = on_target && target.is_some_and(|target| *target.get(&slot) == B::Element::from(value));
+    let result = search(guessed, strategy, target, guess_on_target, steps);
+    if let Ok(Some(solution)) = result {
+      return Ok(Some(solution));
+    }
+    steps.push(TraceStep { slot, value, reason: StepReason::Backtrack });
+    if result.is_err() && !on_target {
+      return Err(Diverged);
+    }
+  }
+  Ok(None)
+}
+
+/// The first naked single in row major order, or else the first hidden single
+/// unit by unit. `None` when the board turns out to be unsatisfiable
+fn next_single<B: Board>(board: &B) -> Option<Option<TraceStep>> {
+  for slot in B::slots() {
+    if board.get(&slot).is_unknown() {
+      let answers = board.possible_answers(&slot);
+      match answers.count() {
+        0 => return None,
+        1 => {
+          let value = answers.elements()[0].into();
+          return Some(Some(TraceStep { slot, value, reason: StepReason::NakedSingle }));
+        },
+        _ => {}
+      }
+    }
+  }
+
+  for unit in B::units().iter().map(Unit::slots::<B>) {
+    let mut present = B::ElementSet::default();
+    for slot in &unit {
+      present.insert(board.get(slot));
+    }
+    for digit in present.complement().elements() {
+      let mut digit_set = B::ElementSet::default();
+      digit_set.insert(&digit);
+      let mut places = unit.iter()
+        .filter(|slot| board.get(slot).is_unknown() && board.possible_answers(slot).intersect(&digit_set).count() > 0);
+      match (places.next(), places.next()) {
+        (None, _) => return None,
+        (Some(slot), None) => {
+          return Some(Some(TraceStep { slot: *slot, value: digit.into(), reason: StepReason::HiddenSingle }));
+        },
+        _ => {}
+      }
+    }
+  }
+  Some(None)
+}
@@ -0,0 +1,197 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::board::*;
+
+/// A validity preserving transformation of a sudoku board.
+///
+/// A transform is made of a digit relabeling, a permutation of the rows, a
+/// permutation of the columns, and an optional transposition. Rows are only
+/// permuted within their band (and bands as a whole), columns are only
+/// permuted within their stack (and stacks as a whole), so applying a
+/// transform to a valid board always gives another valid board.
+///
+/// Transforms can be composed with `then` and inverted with `inverse`:
+///
+/// ```
+/// # use sudoku_rs::*;
+/// let board = Board4x4::default().solve().next().unwrap();
+/// let t = Transform::swap_rows::<Board4x4>(0, 1).then(&Transform::rotate::<Board4x4>());
+/// let restored = t.inverse().apply(&t.apply(&board));
+/// assert_eq!(restored, board);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transform {
+  /// `digits[d]` is the new label of digit `d`. `digits[0]` is always `0`
+  digits: Vec<u8>,
+
+  /// `rows[i]` is the row that row `i` is moved to
+  rows: Vec<usize>,
+
+  /// `columns[j]` is the column that column `j` is moved to
+  columns: Vec<usize>,
+
+  /// Whether the board is transposed after permuting rows and columns
+  transpose: bool,
+}
+
+impl Transform {
+  /// The transform that leaves every board untouched
+  pub fn identity<B: Board>() -> Self {
+    Self {
+      digits: (0..=B::size() as u8).collect(),
+      rows: (0..B::size()).collect(),
+      columns: (0..B::size()).collect(),
+      transpose: false,
+    }
+  }
+
+  /// Assemble a transform from its parts. See the fields of `Transform`
+  pub(crate) fn from_parts(digits: Vec<u8>, rows: Vec<usize>, columns: Vec<usize>, transpose: bool) -> Self {
+    Self { digits, rows, columns, transpose }
+  }
+
+  /// Relabel the digits: digit `i + 1` becomes `digits[i]`. `digits` needs to
+  /// be a permutation of `1..=size`
+  pub fn relabel<B: Board>(digits: &[u8]) -> Self {
+    assert_eq!(digits.len(), B::size(), "Relabeling needs exactly {} digits", B::size());
+    let mut sorted = digits.to_vec();
+    sorted.sort_unstable();
+    assert!(sorted.iter().enumerate().all(|(i, d)| *d as usize == i + 1), "Relabeling {:?} is not a permutation", digits);
+    let mut result = Self::identity::<B>();
+    result.digits[1..].copy_from_slice(digits);
+    result
+  }
+
+  /// Swap two rows residing in the same band
+  pub fn swap_rows<B: Board>(a: usize, b: usize) -> Self {
+    assert_eq!(a / B::block_size(), b / B::block_size(), "Rows {} and {} are not in the same band", a, b);
+    let mut result = Self::identity::<B>();
+    result.rows.swap(a, b);
+    result
+  }
+
+  /// Swap two bands (horizontal groups of blocks)
+  pub fn swap_bands<B: Board>(a: usize, b: usize) -> Self {
+    let mut result = Self::identity::<B>();
+    for k in 0..B::block_size() {
+      result.rows.swap(a * B::block_size() + k, b * B::block_size() + k);
+    }
+    result
+  }
+
+  /// Swap two columns residing in the same stack
+  pub fn swap_columns<B: Board>(a: usize, b: usize) -> Self {
+    assert_eq!(a / B::block_size(), b / B::block_size(), "Columns {} and {} are not in the same stack", a, b);
+    let mut result = Self::identity::<B>();
+    result.columns.swap(a, b);
+    result
+  }
+
+  /// Swap two stacks (vertical groups of blocks)
+  pub fn swap_stacks<B: Board>(a: usize, b: usize) -> Self {
+    let mut result = Self::identity::<B>();
+    for k in 0..B::block_size() {
+      result.columns.swap(a * B::block_size() + k, b * B::block_size() + k);
+    }
+    result
+  }
+
+  /// Mirror the board along its major diagonal
+  pub fn transpose<B: Board>() -> Self {
+    Self { transpose: true, ..Self::identity::<B>() }
+  }
+
+  /// Rotate the board by 90 degrees clockwise
+  pub fn rotate<B: Board>() -> Self {
+    // Reversing the rows and then transposing gives a clockwise rotation
+    Self { rows: (0..B::size()).rev().collect(), transpose: true, ..Self::identity::<B>() }
+  }
+
+  /// Draw a transform uniformly at random from the whole symmetry group
+  pub fn random<B: Board, R: Rng + ?Sized>(rng: &mut R) -> Self {
+    let mut digits = (1..=B::size() as u8).collect::<Vec<_>>();
+    digits.shuffle(rng);
+    let mut result = Self::relabel::<B>(&digits);
+    result.rows = Self::random_line_permutation::<B, R>(rng);
+    result.columns = Self::random_line_permutation::<B, R>(rng);
+    result.transpose = rng.gen();
+    result
+  }
+
+  /// Permute the bands (or stacks) as a whole and the lines within each of them
+  fn random_line_permutation<B: Board, R: Rng + ?Sized>(rng: &mut R) -> Vec<usize> {
+    let block_size = B::block_size();
+    let mut groups = (0..block_size).collect::<Vec<_>>();
+    groups.shuffle(rng);
+    let mut result = vec![0; B::size()];
+    for (group, new_group) in groups.into_iter().enumerate() {
+      let mut lines = (0..block_size).collect::<Vec<_>>();
+      lines.shuffle(rng);
+      for (k, new_k) in lines.into_iter().enumerate() {
+        result[group * block_size + k] = new_group * block_size + new_k;
+      }
+    }
+    result
+  }
+
+  /// Whether the transform also transposes the board
+  pub fn is_transposing(&self) -> bool {
+    self.transpose
+  }
+
+  /// The slot that the element at `slot` is moved to
+  pub fn map_slot(&self, slot: &Slot) -> Slot {
+    let (i, j) = (self.rows[slot.0], self.columns[slot.1]);
+    if self.transpose { (j, i) } else { (i, j) }
+  }
+
+  /// The element that `elem` is relabeled to
+  pub fn map_element<E: BoardElement>(&self, elem: E) -> E {
+    E::from(self.digits[elem.into() as usize])
+  }
+
+  /// Apply the transform to a board
+  pub fn apply<B: Board>(&self, board: &B) -> B {
+    assert_eq!(self.rows.len(), B::size(), "Transform does not match the size of the board");
+    let mut result = board.clone();
+    for slot in B::slots() {
+      result.set(&self.map_slot(&slot), self.map_element(*board.get(&slot)));
+    }
+    result
+  }
+
+  /// The transform that applies `self` first and then `other`
+  pub fn then(&self, other: &Self) -> Self {
+    let compose = |second: &[usize], first: &[usize]| first.iter().map(|i| second[*i]).collect::<Vec<_>>();
+    let (rows, columns) = if self.transpose {
+      // After transposing, our rows are permuted by the other's columns and vice versa
+      (compose(&other.columns, &self.rows), compose(&other.rows, &self.columns))
+    } else {
+      (compose(&other.rows, &self.rows), compose(&other.columns, &self.columns))
+    };
+    Self {
+      digits: self.digits.iter().map(|d| other.digits[*d as usize]).collect(),
+      rows,
+      columns,
+      transpose: self.transpose != other.transpose,
+    }
+  }
+
+  /// The transform that undoes `self`
+  pub fn inverse(&self) -> Self {
+    let invert = |perm: &[usize]| {
+      let mut result = vec![0; perm.len()];
+      for (i, p) in perm.iter().enumerate() { result[*p] = i; }
+      result
+    };
+    let mut digits = vec![0; self.digits.len()];
+    for (d, new_d) in self.digits.iter().enumerate() { digits[*new_d as usize] = d as u8; }
+    let (rows, columns) = if self.transpose {
+      (invert(&self.columns), invert(&self.rows))
+    } else {
+      (invert(&self.rows), invert(&self.columns))
+    };
+    Self { digits, rows, columns, transpose: self.transpose }
+  }
+}
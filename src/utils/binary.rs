@@ -0,0 +1,251 @@
+use std::io::prelude::*;
+
+use crate::Board;
+use super::InputRecord;
+
+/// The first bytes of every binary file
+pub const BINARY_MAGIC : &[u8; 4] = b"SDKB";
+
+/// The version of the binary format written by this library
+pub const BINARY_VERSION : u8 = 1;
+
+/// Size of the header, in bytes
+const HEADER_LEN : usize = 8;
+
+/// Flag set in the header when every record holds a question and its solution
+const FLAG_WITH_SOLUTIONS : u8 = 1;
+
+/// The header of a binary file: the magic bytes, the version of the format,
+/// the board size, and flags, 8 bytes in total.
+///
+/// After the header come the records, all of the same length. Boards are
+/// packed 4 bits per slot (the digit, `0` for unknown), two slots per byte in
+/// row major order, high nibble first. When the records hold solutions, a
+/// record is the packed solution followed by a bitmask of the givens of the
+/// question (one bit per slot, row major, most significant bit first), so a
+/// 9x9 question with its solution takes 52 bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BinaryHeader {
+  pub version: u8,
+  pub board_size: usize,
+  pub with_solutions: bool,
+}
+
+impl BinaryHeader {
+  pub fn new(board_size: usize, with_solutions: bool) -> Self {
+    Self { version: BINARY_VERSION, board_size, with_solutions }
+  }
+
+  pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+    let flags = if self.with_solutions { FLAG_WITH_SOLUTIONS } else { 0 };
+    let [m0, m1, m2, m3] = *BINARY_MAGIC;
+    [m0, m1, m2, m3, self.version, self.board_size as u8, flags, 0]
+  }
+
+  pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+    if bytes.len() < HEADER_LEN || &bytes[..4] != BINARY_MAGIC {
+      return Err("Not a binary sudoku file".to_string());
+    }
+    let (version, board_size, flags) = (bytes[4], bytes[5] as usize, bytes[6]);
+    if version == 0 || version > BINARY_VERSION {
+      return Err(format!("Unsupported binary format version {}", version));
+    }
+    if board_size == 0 || board_size > 15 {
+      return Err(format!("Unsupported board size {}", board_size));
+    }
+    Ok(Self { version, board_size, with_solutions: flags & FLAG_WITH_SOLUTIONS != 0 })
+  }
+
+  fn num_slots(&self) -> usize {
+    self.board_size * self.board_size
+  }
+
+  /// Length of every record, in bytes
+  pub fn record_len(&self) -> usize {
+    let packed = self.num_slots().div_ceil(2);
+    if self.with_solutions { packed + self.num_slots().div_ceil(8) } else { packed }
+  }
+
+  /// Encode a record, made of the digits of a board (or of a question along
+  /// with its solution) in row major order
+  pub fn encode_record(&self, question: &[u8], answer: Option<&[u8]>) -> Result<Vec<u8>, String> {
+    let n = self.num_slots();
+    if question.len() != n || answer.is_some_and(|answer| answer.len() != n) {
+      return Err(format!("Boards must contain {} elements", n));
+    }
+    let board = match (self.with_solutions, answer) {
+      (true, Some(answer)) => answer,
+      (false, None) => question,
+      (true, None) => return Err("Records of this file need a solution".to_string()),
+      (false, Some(_)) => return Err("Records of this file cannot have a solution".to_string()),
+    };
+    let mut bytes = vec![0; self.record_len()];
+    for (k, digit) in board.iter().enumerate() {
+      if *digit as usize > self.board_size {
+        return Err(format!("Invalid element {} for a board of size {}", digit, self.board_size));
+      }
+      bytes[k / 2] |= if k % 2 == 0 { digit << 4 } else { *digit };
+    }
+    if self.with_solutions {
+      let mask = &mut bytes[n.div_ceil(2)..];
+      for (k, (given, solved)) in question.iter().zip(board).enumerate() {
+        if *given != 0 {
+          if given != solved {
+            return Err("The question does not agree with its solution".to_string());
+          }
+          mask[k / 8] |= 0x80 >> (k % 8);
+        }
+      }
+    }
+    Ok(bytes)
+  }
+
+  /// Decode a record of `record_len` bytes, checking that every digit fits the
+  /// board size
+  pub fn decode_record(&self, bytes: &[u8]) -> Result<InputRecord, String> {
+    let n = self.num_slots();
+    if bytes.len() != self.record_len() {
+      return Err(format!("Binary records must have {} bytes", self.record_len()));
+    }
+    let board = (0..n).map(|k| if k % 2 == 0 { bytes[k / 2] >> 4 } else { bytes[k / 2] & 0xf }).collect::<Vec<_>>();
+    if let Some(digit) = board.iter().find(|digit| **digit as usize > self.board_size) {
+      return Err(format!("Invalid element {} for a board of size {}", digit, self.board_size));
+    }
+    if self.with_solutions {
+      let mask = &bytes[n.div_ceil(2)..];
+      let question = (0..n).map(|k| if mask[k / 8] & (0x80 >> (k % 8)) != 0 { board[k] } else { 0 }).collect();
+      Ok(InputRecord { question, answer: Some(board), candidates: None })
+    } else {
+      Ok(InputRecord { question: board, answer: None, candidates: None })
+    }
+  }
+}
+
+/// Check if some content starts like a binary file
+pub fn is_binary(contents: &[u8]) -> bool {
+  contents.starts_with(BINARY_MAGIC)
+}
+
+/// Parse all the records of a binary file
+pub fn parse_binary_records(contents: &[u8]) -> Result<Vec<InputRecord>, String> {
+  BinaryReader::new(contents)?.collect()
+}
+
+/// Writes boards in the binary format to any writer, one record at a time.
+///
+/// ```
+/// # use sudoku_rs::{*, utils::*};
+/// let board = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]);
+/// let mut writer = BinaryWriter::new(vec![], 4, false).unwrap();
+/// writer.write_board(&board).unwrap();
+/// let bytes = writer.into_inner();
+/// assert_eq!(bytes.len(), 8 + 8);
+///
+/// let mut reader = BinaryReader::new(&bytes[..]).unwrap();
+/// assert_eq!(reader.next_boards::<Board4x4>().unwrap(), Some((board, None)));
+/// assert_eq!(reader.next_boards::<Board4x4>().unwrap(), None);
+/// ```
+pub struct BinaryWriter<W: Write> {
+  inner: W,
+  header: BinaryHeader,
+}
+
+impl<W: Write> BinaryWriter<W> {
+  /// Start a binary file by writing its header
+  pub fn new(mut inner: W, board_size: usize, with_solutions: bool) -> Result<Self, String> {
+    let header = BinaryHeader::new(board_size, with_solutions);
+    inner.write_all(&header.to_bytes()).map_err(|_| "Cannot write binary header")?;
+    Ok(Self { inner, header })
+  }
+
+  pub fn header(&self) -> &BinaryHeader {
+    &self.header
+  }
+
+  pub fn write_board<B: Board>(&mut self, board: &B) -> Result<(), String> {
+    self.write_record(&board.to_u8_vec(), None)
+  }
+
+  pub fn write_board_with_solution<B: Board>(&mut self, board: &B, solution: &B) -> Result<(), String> {
+    self.write_record(&board.to_u8_vec(), Some(&solution.to_u8_vec()))
+  }
+
+  pub fn write_record(&mut self, question: &[u8], answer: Option<&[u8]>) -> Result<(), String> {
+    let bytes = self.header.encode_record(question, answer)?;
+    self.inner.write_all(&bytes).map_err(|_| "Cannot write binary record".to_string())
+  }
+
+  pub fn flush(&mut self) -> Result<(), String> {
+    self.inner.flush().map_err(|_| "Cannot flush binary output".to_string())
+  }
+
+  pub fn into_inner(self) -> W {
+    self.inner
+  }
+}
+
+/// Reads the records of a binary file from any reader, one at a time. Also an
+/// iterator over the records
+pub struct BinaryReader<R: Read> {
+  inner: R,
+  header: BinaryHeader,
+}
+
+impl<R: Read> BinaryReader<R> {
+  /// Read the header of a binary file
+  pub fn new(mut inner: R) -> Result<Self, String> {
+    let mut bytes = [0; HEADER_LEN];
+    inner.read_exact(&mut bytes).map_err(|_| "Cannot read binary header")?;
+    let header = BinaryHeader::parse(&bytes)?;
+    Ok(Self { inner, header })
+  }
+
+  pub fn header(&self) -> &BinaryHeader {
+    &self.header
+  }
+
+  /// Read the next record, `None` at the end of the file
+  pub fn next_record(&mut self) -> Result<Option<InputRecord>, String> {
+    let mut bytes = vec![0; self.header.record_len()];
+    let mut len = 0;
+    while len < bytes.len() {
+      match self.inner.read(&mut bytes[len..]) {
+        Ok(0) => break,
+        Ok(n) => len += n,
+        Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {},
+        Err(_) => return Err("Cannot read binary record".to_string()),
+      }
+    }
+    match len {
+      0 => Ok(None),
+      len if len < bytes.len() => Err("Truncated binary record".to_string()),
+      _ => self.header.decode_record(&bytes).map(Some),
+    }
+  }
+
+  /// Read the next record as boards: the question, and its solution if the
+  /// file has solutions
+  pub fn next_boards<B: Board>(&mut self) -> Result<Option<(B, Option<B>)>, String> {
+    if B::size() != self.header.board_size {
+      return Err(format!("The file holds boards of size {}", self.header.board_size));
+    }
+    match self.next_record()? {
+      Some(record) => {
+        let answer = match &record.answer {
+          Some(answer) => Some(B::try_from_u8_vec(answer)?),
+          None => None,
+        };
+        Ok(Some((B::try_from_u8_vec(&record.question)?, answer)))
+      },
+      None => Ok(None),
+    }
+  }
+}
+
+impl<R: Read> Iterator for BinaryReader<R> {
+  type Item = Result<InputRecord, String>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next_record().transpose()
+  }
+}
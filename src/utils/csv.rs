@@ -0,0 +1,53 @@
+use crate::{Board, BoardElement, Difficulty, digits_from_line};
+use super::InputRecord;
+
+/// The header line of CSV files. Boards are written as single lines of digits
+/// (see `Board::to_line`), `clues` is the number of givens of the question,
+/// and `difficulty` its rating (see `Board::difficulty`), which may be left
+/// empty
+pub const CSV_HEADER : &str = "id,question,answer,clues,difficulty";
+
+/// The fields of a CSV line. Fields may be quoted, but cannot hold commas
+fn csv_fields(line: &str) -> Vec<&str> {
+  line.split(',').map(|field| field.trim().trim_matches('"')).collect()
+}
+
+/// Check if some content starts like a CSV file, with a header line holding a
+/// `question` column
+pub fn is_csv(contents: &str) -> bool {
+  contents.trim_start_matches('\u{feff}').lines().next().is_some_and(|line| csv_fields(line).contains(&"question"))
+}
+
+/// Parse the records of a CSV file. Columns are found by their names in the
+/// header, so only `question` is required, and an empty `answer` means the
+/// record has no answer. The other columns are ignored when reading
+pub fn parse_csv_records(contents: &str) -> Result<Vec<InputRecord>, String> {
+  let mut lines = contents.trim_start_matches('\u{feff}').lines().filter(|line| !line.trim().is_empty());
+  let header = csv_fields(lines.next().ok_or("Missing CSV header")?);
+  let column = |name: &str| header.iter().position(|field| *field == name);
+  let question_column = column("question").ok_or("Missing question column in CSV header")?;
+  let answer_column = column("answer");
+  lines.enumerate().map(|(k, line)| {
+    let fields = csv_fields(line);
+    let board = |column: usize| digits_from_line(fields[column]).map_err(|err| format!("Row {} of the CSV file: {}", k + 1, err));
+    if fields.len() != header.len() {
+      return Err(format!("Row {} of the CSV file has {} fields instead of {}", k + 1, fields.len(), header.len()));
+    }
+    let question = board(question_column)?;
+    let answer = match answer_column {
+      Some(column) if !fields[column].is_empty() => Some(board(column)?),
+      _ => None,
+    };
+    Ok(InputRecord { question, answer, candidates: None })
+  }).collect()
+}
+
+/// A CSV line for a question with its answer, or for a board alone (left
+/// without answer). The columns are the ones of `CSV_HEADER`, the difficulty
+/// being left empty when not given, since rating a question means solving it
+pub fn csv_row<B: Board>(id: usize, question: &B, answer: Option<&B>, difficulty: Option<Difficulty>) -> String {
+  let clues = B::slots().filter(|slot| question.get(slot).is_answer()).count();
+  let answer = answer.map(Board::to_line).unwrap_or_default();
+  let difficulty = difficulty.map(|difficulty| difficulty.to_string()).unwrap_or_default();
+  format!("{},{},{},{},{}", id, question.to_line(), answer, clues, difficulty)
+}
@@ -0,0 +1,320 @@
+use serde_json::Value;
+
+use crate::{Board, Constraint, Diagonal, Slot, VariantPuzzle};
+
+/// The keys of f-puzzles files that only draw on the grid, or hold no rule
+const COSMETIC_KEYS : &[&str] = &[
+  "size", "grid", "title", "author", "ruleset", "solution", "text", "line", "rectangle", "circle", "cage",
+  "highlightConflicts", "disabledlogic", "truecandidatesoptions",
+];
+
+/// A puzzle read from an f-puzzles file, see `parse_fpuzzles`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FPuzzle {
+  pub title: Option<String>,
+  pub size: usize,
+
+  /// The givens in row major order, `0` for unknown
+  pub givens: Vec<u8>,
+
+  /// The rules of the puzzle that can be solved for
+  pub constraints: Vec<Constraint>,
+
+  /// The rules of the puzzle that cannot be solved for, by their f-puzzles
+  /// names (e.g. `sandwichsum`)
+  pub unsupported: Vec<String>,
+}
+
+impl FPuzzle {
+  /// The puzzle on a board, leaving out the unsupported rules
+  pub fn to_variant<B: Board>(&self) -> Result<VariantPuzzle<B>, String> {
+    if self.size != B::size() {
+      return Err(format!("The puzzle is of size {}, not {}", self.size, B::size()));
+    }
+    let puzzle = VariantPuzzle::new(B::from_u8_vec(&self.givens));
+    Ok(self.constraints.iter().cloned().fold(puzzle, VariantPuzzle::constraint))
+  }
+}
+
+/// Check if some content looks like an f-puzzles file: json with a `grid`, or
+/// a compressed payload, alone or in an f-puzzles or SudokuPad link
+pub fn is_fpuzzles(contents: &str) -> bool {
+  let contents = contents.trim();
+  if contents.starts_with('{') {
+    serde_json::from_str::<Value>(contents).is_ok_and(|value| value["grid"].is_array())
+  } else {
+    // Compressed json objects all start alike
+    contents.contains("f-puzzles.com") || contents.contains("fpuzzles") || contents.starts_with("N4Ig")
+  }
+}
+
+/// Parse a puzzle of f-puzzles (also read by SudokuPad), either as json or as
+/// the compressed payload of its links, alone or in the link. Givens, killer
+/// cages, thermometers, arrows, diagonals, and irregular regions are
+/// supported. The other rules are listed in `FPuzzle::unsupported`, while the
+/// purely cosmetic ones (lines, text, ...) are left out.
+///
+/// ```
+/// # use sudoku_rs::{*, utils::*};
+/// let puzzle = parse_fpuzzles(r#"{"size": 4, "grid": [
+///   [{"value": 1, "given": true}, {}, {}, {}], [{}, {}, {}, {}], [{}, {}, {}, {}], [{}, {}, {}, {}]
+/// ], "diagonal-": true, "sandwichsum": [{"cell": "R1C0", "value": "5"}]}"#).unwrap();
+/// assert_eq!(puzzle.givens[0], 1);
+/// assert_eq!(puzzle.constraints, vec![Constraint::Diagonal(Diagonal::Negative)]);
+/// assert_eq!(puzzle.unsupported, vec!["sandwichsum".to_string()]);
+/// ```
+pub fn parse_fpuzzles(contents: &str) -> Result<FPuzzle, String> {
+  let contents = contents.trim();
+  let json = if contents.starts_with('{') {
+    contents.to_string()
+  } else {
+    decompress_from_base64(&fpuzzles_payload(contents))?
+  };
+  let value = serde_json::from_str::<Value>(&json).map_err(|_| "Cannot parse f-puzzles json")?;
+  let size = value["size"].as_u64().ok_or("Missing size in f-puzzles json")? as usize;
+  let rows = value["grid"].as_array().filter(|rows| rows.len() == size).ok_or("Invalid grid in f-puzzles json")?;
+  let cells = rows.iter().map(|row| {
+    row.as_array().filter(|row| row.len() == size).cloned().ok_or_else(|| "Invalid grid in f-puzzles json".to_string())
+  }).collect::<Result<Vec<_>, _>>()?.concat();
+
+  let givens = cells.iter().map(|cell| {
+    match cell["value"].as_u64() {
+      Some(d) if cell["given"].as_bool() == Some(true) && d as usize <= size => Ok(d as u8),
+      Some(d) if cell["given"].as_bool() == Some(true) => Err(format!("Invalid given {} for a board of size {}", d, size)),
+      _ => Ok(0),
+    }
+  }).collect::<Result<Vec<_>, _>>()?;
+
+  let mut constraints = vec![];
+  if let Some(regions) = regions(&cells, size)? {
+    constraints.push(Constraint::Regions(regions));
+  }
+  if value["diagonal+"].as_bool() == Some(true) {
+    constraints.push(Constraint::Diagonal(Diagonal::Positive));
+  }
+  if value["diagonal-"].as_bool() == Some(true) {
+    constraints.push(Constraint::Diagonal(Diagonal::Negative));
+  }
+  for cage in items(&value, "killercage") {
+    let sum = match &cage["value"] {
+      Value::String(s) if !s.trim().is_empty() => Some(s.trim().parse().map_err(|_| format!("Invalid killer cage sum {}", s))?),
+      Value::Number(n) => Some(n.as_u64().ok_or("Invalid killer cage sum")? as u32),
+      _ => None,
+    };
+    constraints.push(Constraint::Cage { slots: slots(&cage["cells"], size)?, sum });
+  }
+  for thermometer in items(&value, "thermometer") {
+    for line in thermometer["lines"].as_array().ok_or("Invalid thermometer")? {
+      constraints.push(Constraint::Thermometer(slots(line, size)?));
+    }
+  }
+  for arrow in items(&value, "arrow") {
+    let circle = slots(&arrow["cells"], size)?;
+    // Every line starts in the circle and is an arrow on its own
+    for line in arrow["lines"].as_array().ok_or("Invalid arrow")? {
+      let line = slots(line, size)?.into_iter().filter(|slot| !circle.contains(slot)).collect();
+      constraints.push(Constraint::Arrow { circle: circle.clone(), arrow: line });
+    }
+  }
+
+  let supported = ["diagonal+", "diagonal-", "killercage", "thermometer", "arrow"];
+  let unsupported = value.as_object().ok_or("Invalid f-puzzles json")?.iter()
+    .filter(|(key, value)| !COSMETIC_KEYS.contains(&key.as_str()) && !supported.contains(&key.as_str()) && is_set(value))
+    .map(|(key, _)| key.clone())
+    .collect();
+  let title = value["title"].as_str().map(str::to_string);
+  Ok(FPuzzle { title, size, givens, constraints, unsupported })
+}
+
+/// The entries of a rule, none when the rule is missing
+fn items<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+  value[key].as_array().map_or(&[], Vec::as_slice)
+}
+
+/// Whether a rule of the json is in use, rules being turned off by `false` or
+/// empty arrays
+fn is_set(value: &Value) -> bool {
+  match value {
+    Value::Null | Value::Bool(false) => false,
+    Value::Array(items) => !items.is_empty(),
+    Value::Object(items) => !items.is_empty(),
+    _ => true,
+  }
+}
+
+/// Parse an array of `R1C1` cell names, counted from 1
+fn slots(value: &Value, size: usize) -> Result<Vec<Slot>, String> {
+  let cells = value.as_array().ok_or("Expected an array of cells")?;
+  cells.iter().map(|cell| {
+    let name = cell.as_str().ok_or("Expected a cell name")?;
+    let slot = name.strip_prefix('R').and_then(|rest| {
+      let (row, column) = rest.split_once('C')?;
+      Some((row.parse::<usize>().ok()?.checked_sub(1)?, column.parse::<usize>().ok()?.checked_sub(1)?))
+    });
+    slot.filter(|(i, j)| *i < size && *j < size).ok_or_else(|| format!("Invalid cell {}", name))
+  }).collect()
+}
+
+/// The irregular regions given by the `region` of the cells (counted from 0),
+/// `None` when the regions are the usual blocks
+fn regions(cells: &[Value], size: usize) -> Result<Option<Vec<Vec<Slot>>>, String> {
+  let block_size = (size as f64).sqrt() as usize;
+  if block_size * block_size != size {
+    return Err(format!("Unsupported board size {}", size));
+  }
+  let mut regions = vec![vec![]; size];
+  let mut irregular = false;
+  for (k, cell) in cells.iter().enumerate() {
+    let slot = (k / size, k % size);
+    let block = slot.0 / block_size * block_size + slot.1 / block_size;
+    let region = match cell["region"].as_u64() {
+      Some(region) => region as usize,
+      None => block,
+    };
+    irregular |= region != block;
+    regions.get_mut(region).ok_or_else(|| format!("Invalid region {}", region))?.push(slot);
+  }
+  if regions.iter().any(|region| region.len() != size) {
+    return Err("Every region needs to have as many cells as the board size".to_string());
+  }
+  Ok(Some(regions).filter(|_| irregular))
+}
+
+/// The compressed payload of a link, e.g. the `load` parameter of f-puzzles
+/// links or what follows `fpuzzles` in SudokuPad links, with its percent
+/// encoding decoded
+fn fpuzzles_payload(link: &str) -> String {
+  let payload = match (link.find("load="), link.rfind("fpuzzles")) {
+    (Some(start), _) => &link[start + "load=".len()..],
+    (None, Some(start)) => &link[start + "fpuzzles".len()..],
+    (None, None) => link,
+  };
+  let payload = payload.split(['&', '#']).next().unwrap_or_default();
+  let mut result = String::new();
+  let mut chars = payload.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '%' => {
+        let code = chars.by_ref().take(2).collect::<String>();
+        match u8::from_str_radix(&code, 16) {
+          Ok(byte) => result.push(byte as char),
+          Err(_) => result.push_str(&code),
+        }
+      },
+      // `+` turns into a space when links are decoded as forms
+      ' ' => result.push('+'),
+      c => result.push(c),
+    }
+  }
+  result
+}
+
+/// Decompress the output of `compressToBase64` of the lz-string javascript
+/// library, which f-puzzles uses for its links. The URI safe alphabet of
+/// `compressToEncodedURIComponent` is accepted as well
+///
+/// ```
+/// # use sudoku_rs::utils::*;
+/// assert_eq!(decompress_from_base64("BYUwNmD2Q===").unwrap(), "hello");
+/// ```
+pub fn decompress_from_base64(input: &str) -> Result<String, String> {
+  const ALPHABET : &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+  if input.trim().is_empty() {
+    return Ok(String::new());
+  }
+  let values = input.trim().bytes().map(|c| match c {
+    b'-' => Ok(63),
+    b'$' => Ok(64),
+    c => ALPHABET.iter().position(|a| *a == c).map(|v| v as u32).ok_or_else(|| format!("Invalid character {:?} in compressed data", c as char)),
+  }).collect::<Result<Vec<_>, _>>()?;
+  let units = lz_decompress(&values, 32).ok_or("Invalid compressed data")?;
+  String::from_utf16(&units).map_err(|_| "Invalid text in compressed data".to_string())
+}
+
+/// Reads the bits of the lz-string stream, `reset` being the highest bit of
+/// every value (32 for base64, 6 bits per value)
+struct BitReader<'a> {
+  values: &'a [u32],
+  reset: u32,
+  index: usize,
+  position: u32,
+}
+
+impl<'a> BitReader<'a> {
+  /// Read `n` bits, the first one being the lowest. Bits past the end of the
+  /// stream are zeros
+  fn read(&mut self, n: u32) -> u32 {
+    let mut bits = 0;
+    for k in 0..n {
+      let value = self.values.get(self.index).copied().unwrap_or(0);
+      if value & self.position != 0 {
+        bits |= 1 << k;
+      }
+      self.position >>= 1;
+      if self.position == 0 {
+        self.position = self.reset;
+        self.index += 1;
+      }
+    }
+    bits
+  }
+}
+
+/// The decompression of lz-string, giving UTF-16 code units. The dictionary
+/// starts with three codes: a new 8 bit character, a new 16 bit character,
+/// and the end of the stream
+fn lz_decompress(values: &[u32], reset: u32) -> Option<Vec<u16>> {
+  let mut reader = BitReader { values, reset, index: 0, position: reset };
+  let mut dictionary : Vec<Vec<u16>> = vec![vec![], vec![], vec![]];
+  let (mut enlarge_in, mut num_bits) = (4u32, 3u32);
+
+  let first = match reader.read(2) {
+    0 => reader.read(8) as u16,
+    1 => reader.read(16) as u16,
+    _ => return Some(vec![]),
+  };
+  dictionary.push(vec![first]);
+  let mut w = vec![first];
+  let mut result = w.clone();
+
+  loop {
+    if reader.index > values.len() {
+      return None;
+    }
+    let mut code = reader.read(num_bits) as usize;
+    match code {
+      0 | 1 => {
+        let c = reader.read(if code == 0 { 8 } else { 16 }) as u16;
+        dictionary.push(vec![c]);
+        code = dictionary.len() - 1;
+        enlarge_in -= 1;
+      },
+      2 => return Some(result),
+      _ => {}
+    }
+    if enlarge_in == 0 {
+      enlarge_in = 1 << num_bits;
+      num_bits += 1;
+    }
+
+    let entry = match dictionary.get(code) {
+      Some(entry) => entry.clone(),
+      None if code == dictionary.len() => {
+        let mut entry = w.clone();
+        entry.push(w[0]);
+        entry
+      },
+      None => return None,
+    };
+    result.extend_from_slice(&entry);
+    let mut next = w;
+    next.push(entry[0]);
+    dictionary.push(next);
+    enlarge_in -= 1;
+    w = entry;
+    if enlarge_in == 0 {
+      enlarge_in = 1 << num_bits;
+      num_bits += 1;
+    }
+  }
+}
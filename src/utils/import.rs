@@ -0,0 +1,158 @@
+use crate::{Board, Board9x9};
+
+/// The file formats of desktop sudoku tools that can be imported
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PuzzleFormat {
+  /// SadMan Sudoku `.sdk`: optional `#` header lines (author, description,
+  /// ...), then 9 lines of 9 digits with `.` for empty slots. Files with
+  /// sections hold the grid in their `[Puzzle]` section
+  Sdk,
+
+  /// Simple Sudoku `.ss`: 9 rows of 9 digits drawn as grid art, with `|`
+  /// between blocks and `-` lines between bands
+  SimpleSudoku,
+
+  /// HoDoKu pencil mark grids (also written by Simple Sudoku): every slot
+  /// holds its candidates, a slot with a single candidate being solved
+  HoDoKu,
+}
+
+impl std::fmt::Display for PuzzleFormat {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let name = match self {
+      PuzzleFormat::Sdk => "sdk",
+      PuzzleFormat::SimpleSudoku => "ss",
+      PuzzleFormat::HoDoKu => "hodoku",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+/// A puzzle read from a file of a desktop sudoku tool
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportedPuzzle {
+  pub format: PuzzleFormat,
+  pub board: Board9x9,
+
+  /// The candidates of every slot in row major order, each in increasing
+  /// order, when the file stores them
+  pub candidates: Option<Vec<Vec<u8>>>,
+}
+
+impl ImportedPuzzle {
+  /// Check that every known slot of `board` holds one of the stored
+  /// candidates of the slot
+  pub fn allows(&self, board: &Board9x9) -> bool {
+    self.candidates.as_ref().is_none_or(|candidates| candidates_allow(candidates, &board.to_u8_vec()))
+  }
+}
+
+/// Check that every known digit of a board (row major, `0` for unknown) is one
+/// of the candidates of its slot
+pub fn candidates_allow(candidates: &[Vec<u8>], digits: &[u8]) -> bool {
+  digits.iter().zip(candidates).all(|(d, candidates)| *d == 0 || candidates.contains(d))
+}
+
+/// Find out the format of the content of a file, `None` when it is none of
+/// the formats of `PuzzleFormat`
+pub fn detect_puzzle_format(contents: &str) -> Option<PuzzleFormat> {
+  if pencil_mark_rows(contents).is_some() {
+    Some(PuzzleFormat::HoDoKu)
+  } else if contents.contains('|') && grid_art_rows(contents).is_some() {
+    Some(PuzzleFormat::SimpleSudoku)
+  } else if sdk_rows(contents).is_some() {
+    Some(PuzzleFormat::Sdk)
+  } else {
+    None
+  }
+}
+
+/// Parse the content of a file in any of the formats of `PuzzleFormat`
+///
+/// ```
+/// # use sudoku_rs::{*, utils::*};
+/// let contents = "#A Someone\n#D A puzzle\n\
+///   ..3.2.6..\n9..3.5..1\n..18.64..\n..81.29..\n7.......8\n..67.82..\n..26.95..\n8..2.3..9\n..5.1.3..\n";
+/// let puzzle = parse_puzzle(contents).unwrap();
+/// assert_eq!(puzzle.format, PuzzleFormat::Sdk);
+/// assert_eq!(puzzle.board.to_line()[..9], *"..3.2.6..");
+/// ```
+pub fn parse_puzzle(contents: &str) -> Result<ImportedPuzzle, String> {
+  let format = detect_puzzle_format(contents).ok_or("Unknown puzzle format")?;
+  let (rows, candidates) = match format {
+    PuzzleFormat::Sdk => (sdk_rows(contents), None),
+    PuzzleFormat::SimpleSudoku => (grid_art_rows(contents), None),
+    PuzzleFormat::HoDoKu => {
+      let candidates = pencil_mark_rows(contents).expect("Detected pencil mark grid").concat();
+      let digits = candidates.iter().map(|c| if c.len() == 1 { c[0] } else { 0 }).collect::<Vec<_>>();
+      (Some(digits.chunks(9).map(<[u8]>::to_vec).collect()), Some(candidates))
+    },
+  };
+  let digits = rows.expect("Detected puzzle rows").concat();
+  Ok(ImportedPuzzle { format, board: Board9x9::from_u8_vec(&digits), candidates })
+}
+
+/// The digit of a cell, `None` when the character is not a cell
+fn cell(c: char) -> Option<u8> {
+  match c {
+    '1'..='9' => Some(c as u8 - b'0'),
+    '.' | '0' | 'x' | 'X' => Some(0),
+    _ => None,
+  }
+}
+
+/// Parse 9 lines of 9 cells
+fn rows_of_cells<'a, I: Iterator<Item = &'a str>>(lines: I) -> Option<Vec<Vec<u8>>> {
+  let rows = lines.take(9).map(|line| {
+    let row = line.trim().chars().map(cell).collect::<Option<Vec<_>>>()?;
+    Some(row).filter(|row| row.len() == 9)
+  }).collect::<Option<Vec<_>>>()?;
+  Some(rows).filter(|rows| rows.len() == 9)
+}
+
+/// The rows of an `.sdk` file: the first 9 lines after the headers, or after
+/// the `[Puzzle]` section header when the file has sections
+fn sdk_rows(contents: &str) -> Option<Vec<Vec<u8>>> {
+  let lines = contents.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+  match contents.lines().position(|line| line.trim() == "[Puzzle]") {
+    Some(start) => {
+      let section = contents.lines().skip(start + 1).map(str::trim).take_while(|line| !line.starts_with('['));
+      rows_of_cells(section.filter(|line| !line.is_empty()))
+    },
+    None => rows_of_cells(lines),
+  }
+}
+
+/// The rows of a grid drawn with `|` between blocks and lines of `-` (with
+/// corners such as `+` or `*`) between bands
+fn grid_art_rows(contents: &str) -> Option<Vec<Vec<u8>>> {
+  let is_border = |line: &&str| line.contains('-') && line.chars().all(|c| "-+*|.:' ".contains(c));
+  let lines = contents.lines().map(str::trim).filter(|line| !line.is_empty() && !is_border(line));
+  let lines = lines.map(|line| line.chars().filter(|c| *c != '|' && !c.is_whitespace()).collect::<String>()).collect::<Vec<_>>();
+  if lines.len() != 9 {
+    return None;
+  }
+  rows_of_cells(lines.iter().map(String::as_str))
+}
+
+/// The candidates in a pencil mark grid: the lines holding digits are the
+/// rows, where the candidates of the slots are separated by spaces and `|`
+fn pencil_mark_rows(contents: &str) -> Option<Vec<Vec<Vec<u8>>>> {
+  let lines = contents.lines().filter(|line| line.chars().any(|c| c.is_ascii_digit())).collect::<Vec<_>>();
+  if lines.len() != 9 {
+    return None;
+  }
+  let rows = lines.iter().map(|line| {
+    let inner = line.trim().trim_matches(|c| matches!(c, '|' | ':' | '*' | '.' | '\''));
+    let slots = inner.split(|c: char| c.is_whitespace() || c == '|').filter(|token| !token.is_empty()).map(|token| {
+      let digits = token.chars().map(|c| c.to_digit(10).filter(|d| *d > 0).map(|d| d as u8)).collect::<Option<Vec<_>>>()?;
+      let mut digits = digits;
+      digits.sort_unstable();
+      digits.dedup();
+      Some(digits)
+    }).collect::<Option<Vec<_>>>()?;
+    Some(slots).filter(|slots| slots.len() == 9)
+  }).collect::<Option<Vec<_>>>()?;
+  // A grid where every slot holds one digit is drawn alike in both formats
+  Some(rows).filter(|rows| rows.iter().flatten().any(|slot| slot.len() > 1))
+}
@@ -0,0 +1,184 @@
+use std::fs::File;
+use std::io::prelude::*;
+
+use serde_json::Value;
+
+use crate::Board;
+
+use super::binary::*;
+use super::csv::*;
+use super::import::*;
+
+/// A record read from an input file: a question (row major, `0` for unknown),
+/// its answer if the file contains one, and the candidates of every slot if
+/// the file stores them (see `ImportedPuzzle`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputRecord {
+  pub question: Vec<u8>,
+  pub answer: Option<Vec<u8>>,
+  pub candidates: Option<Vec<Vec<u8>>>,
+}
+
+impl InputRecord {
+  /// The size of the board in this record, e.g. 9 for a 9x9 board
+  pub fn size(&self) -> usize {
+    (self.question.len() as f64).sqrt() as usize
+  }
+
+  /// Check that the record contains square boards of matching sizes, with every
+  /// element being in the range `0..=size`
+  pub fn check(&self) -> Result<(), String> {
+    let size = self.size();
+    let check_board = |board: &Vec<u8>| {
+      if board.len() != size * size || size == 0 {
+        Err(format!("Board with {} elements is not a square board of size {}", board.len(), size))
+      } else if let Some(n) = board.iter().find(|n| **n as usize > size) {
+        Err(format!("Invalid element {} for a board of size {}", n, size))
+      } else {
+        Ok(())
+      }
+    };
+    check_board(&self.question)?;
+    if let Some(answer) = &self.answer {
+      check_board(answer)?;
+    }
+    match &self.candidates {
+      Some(candidates) if candidates.len() != size * size => Err(format!("Candidates of {} slots for a board of size {}", candidates.len(), size)),
+      _ => Ok(()),
+    }
+  }
+
+  /// Check that every known slot of a board (row major, `0` for unknown) holds
+  /// one of the stored candidates of the slot
+  pub fn allows(&self, digits: &[u8]) -> bool {
+    self.candidates.as_ref().is_none_or(|candidates| candidates_allow(candidates, digits))
+  }
+}
+
+/// Read all the records from a file, either a binary file (see
+/// `BinaryHeader`), json, or CSV. See `parse_records` for the accepted text
+/// formats
+pub fn read_records(filename: &str) -> Result<Vec<InputRecord>, String> {
+  let mut file = File::open(filename).map_err(|_| "Cannot open input file")?;
+  let mut contents = vec![];
+  file.read_to_end(&mut contents).map_err(|_| "Cannot read from input file")?;
+  if is_binary(&contents) {
+    return parse_binary_records(&contents);
+  }
+  parse_records(&String::from_utf8(contents).map_err(|_| "Cannot parse input file")?)
+}
+
+/// Parse records from the content of a file. The content can be
+///
+/// - a single board, either nested `[[x, x, ...], [x, x, ...]]` or flat `[x, x, ...]`
+/// - a json array of boards or of `{"q": board, "a": board}` records, as written
+///   by `sudoku-gen`
+/// - newline delimited json, one board or record per line
+/// - CSV with a header line, see `parse_csv_records`
+/// - a puzzle file of a desktop sudoku tool, see `parse_puzzle`
+pub fn parse_records(contents: &str) -> Result<Vec<InputRecord>, String> {
+  if is_csv(contents) {
+    return parse_csv_records(contents);
+  }
+  if let Ok(value) = serde_json::from_str::<Value>(contents) {
+    // An array of flat boards may also read as a nested board, e.g. 16 flat
+    // 4x4 boards. The rows of a nested board are never boards of a supported
+    // size, so the array is taken as records first
+    if let Value::Array(values) = &value {
+      let records = values.iter().map(record_from_json).collect::<Option<Vec<_>>>();
+      if let Some(records) = records.filter(|records| records.iter().all(|record| [16, 81].contains(&record.question.len()))) {
+        return Ok(records);
+      }
+    }
+    if let Some(record) = record_from_json(&value) {
+      return Ok(vec![record]);
+    }
+    if let Value::Array(values) = &value {
+      return values.iter().map(|value| record_from_json(value).ok_or_else(|| "Cannot parse record in input file".to_string())).collect();
+    }
+  }
+
+  // Try the formats of desktop sudoku tools
+  if detect_puzzle_format(contents).is_some() {
+    let puzzle = parse_puzzle(contents)?;
+    return Ok(vec![InputRecord { question: puzzle.board.to_u8_vec(), answer: None, candidates: puzzle.candidates }]);
+  }
+
+  // Try newline delimited json
+  contents.lines().filter(|line| !line.trim().is_empty()).map(|line| {
+    serde_json::from_str::<Value>(line).ok()
+      .and_then(|value| record_from_json(&value))
+      .ok_or_else(|| "Cannot parse input file".to_string())
+  }).collect()
+}
+
+/// A puzzle along with the entries of a player, where `entries` holds the
+/// givens of the puzzle and the digits filled in by the player
+#[derive(Clone, Debug)]
+pub struct EntryRecord {
+  pub puzzle: Vec<u8>,
+  pub entries: Vec<u8>,
+}
+
+/// Read all the entry records from a file. See `parse_entry_records`
+pub fn read_entry_records(filename: &str) -> Result<Vec<EntryRecord>, String> {
+  let mut file = File::open(filename).map_err(|_| "Cannot open input file")?;
+  let mut contents = String::new();
+  file.read_to_string(&mut contents).map_err(|_| "Cannot read from input file")?;
+  parse_entry_records(&contents)
+}
+
+/// Parse entry records from the content of a file. The content can be a single
+/// `{"puzzle": board, "entries": board}` record, a json array of them, or
+/// newline delimited json. `"q"` is accepted in place of `"puzzle"`, so that
+/// the records written by `sudoku-gen` only need the entries to be added
+pub fn parse_entry_records(contents: &str) -> Result<Vec<EntryRecord>, String> {
+  let records = match serde_json::from_str::<Value>(contents) {
+    Ok(Value::Array(values)) => values,
+    Ok(value) => vec![value],
+    Err(_) => contents.lines().filter(|line| !line.trim().is_empty()).map(|line| {
+      serde_json::from_str::<Value>(line).map_err(|_| "Cannot parse input file".to_string())
+    }).collect::<Result<_, _>>()?,
+  };
+  records.iter().map(|value| {
+    let puzzle = value.get("puzzle").or_else(|| value.get("q")).and_then(board_from_json);
+    let entries = value.get("entries").and_then(board_from_json);
+    match (puzzle, entries) {
+      (Some(puzzle), Some(entries)) if puzzle.len() == entries.len() => Ok(EntryRecord { puzzle, entries }),
+      _ => Err("Cannot parse record in input file, expected a puzzle and entries of the same size".to_string()),
+    }
+  }).collect()
+}
+
+fn record_from_json(value: &Value) -> Option<InputRecord> {
+  match value {
+    Value::Object(object) => {
+      let question = board_from_json(object.get("q")?)?;
+      let answer = match object.get("a") {
+        Some(answer) => Some(board_from_json(answer)?),
+        None => None,
+      };
+      Some(InputRecord { question, answer, candidates: None })
+    },
+    _ => board_from_json(value).map(|question| InputRecord { question, answer: None, candidates: None }),
+  }
+}
+
+/// Parse a nested `[[x, x, ...], [x, x, ...]]` or flat `[x, x, ...]` board
+fn board_from_json(value: &Value) -> Option<Vec<u8>> {
+  let values = value.as_array()?;
+  if values.iter().all(Value::is_number) {
+    return values.iter().map(|n| n.as_u64().filter(|n| *n <= u8::MAX as u64).map(|n| n as u8)).collect();
+  }
+  let mut result = vec![];
+  for row in values {
+    let row = row.as_array()?;
+    if row.len() != values.len() {
+      return None;
+    }
+    for n in row {
+      result.push(n.as_u64().filter(|n| *n <= u8::MAX as u64)? as u8);
+    }
+  }
+  Some(result)
+}
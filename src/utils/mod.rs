@@ -1,3 +1,15 @@
+mod binary;
+mod csv;
+mod fpuzzles;
+mod import;
+mod input;
+mod npy;
 mod output;
 
-pub use output::*;
\ No newline at end of file
+pub use binary::*;
+pub use csv::*;
+pub use fpuzzles::*;
+pub use import::*;
+pub use input::*;
+pub use npy::*;
+pub use output::*;
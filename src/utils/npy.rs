@@ -0,0 +1,220 @@
+use std::fs::{File, OpenOptions};
+use std::io::SeekFrom;
+use std::io::prelude::*;
+
+use crate::Board;
+
+const NPY_MAGIC : &[u8] = b"\x93NUMPY\x01\x00";
+
+/// Width reserved for the number of rows in the header, so that the header
+/// keeps its length when the number is filled in at the end
+const ROWS_WIDTH : usize = 20;
+
+/// Writes an array to a `.npy` file one row at a time. The number of rows is
+/// only known at the end, so the header is written again by `finish`
+pub struct NpyWriter {
+  file: File,
+  descr: &'static str,
+  row_shape: Vec<usize>,
+  rows: usize,
+}
+
+impl NpyWriter {
+  /// Create a `.npy` file for an array of rows of the given shape, with the
+  /// given numpy type description (e.g. `|u1`)
+  pub fn create(filename: &str, descr: &'static str, row_shape: Vec<usize>) -> Result<Self, String> {
+    let file = File::create(filename).map_err(|_| format!("Cannot create file {}", filename))?;
+    let mut writer = Self { file, descr, row_shape, rows: 0 };
+    writer.write_header()?;
+    Ok(writer)
+  }
+
+  /// Open a `.npy` file written by an `NpyWriter` to append rows to it. The
+  /// array in the file needs to have the given type and row shape
+  pub fn append(filename: &str, descr: &'static str, row_shape: Vec<usize>) -> Result<Self, String> {
+    let mut file = OpenOptions::new().read(true).write(true).open(filename).map_err(|_| format!("Cannot open file {}", filename))?;
+    let mut prefix = [0; 10];
+    file.read_exact(&mut prefix).map_err(|_| format!("Cannot read file {}", filename))?;
+    let header_len = u16::from_le_bytes([prefix[8], prefix[9]]) as usize;
+    let mut header = vec![0; header_len];
+    file.read_exact(&mut header).map_err(|_| format!("Cannot read file {}", filename))?;
+    let header = String::from_utf8_lossy(&header).to_string();
+    let rows = header_rows(&header).ok_or_else(|| format!("Invalid header in file {}", filename))?;
+
+    let mut writer = Self { file, descr, row_shape, rows };
+    if &prefix[..8] != NPY_MAGIC || header != writer.header_dict() {
+      return Err(format!("File {} does not hold an array of the same type and shape", filename));
+    }
+    // The header is only up to date once finished, so count the rows from the
+    // length of the file, dropping a row left incomplete by an interruption
+    let data_start = (10 + header_len) as u64;
+    let data_len = writer.file.metadata().map_err(|_| format!("Cannot read file {}", filename))?.len() - data_start;
+    let row_len = writer.row_shape.iter().product::<usize>() as u64;
+    writer.rows = (data_len / row_len) as usize;
+    writer.file.set_len(data_start + writer.rows as u64 * row_len).map_err(|_| format!("Cannot truncate file {}", filename))?;
+    writer.file.seek(SeekFrom::End(0)).map_err(|_| "Cannot seek file")?;
+    Ok(writer)
+  }
+
+  /// The header dictionary, padded with spaces and a newline so that the data
+  /// starts at a multiple of 64 bytes
+  fn header_dict(&self) -> String {
+    let shape = self.row_shape.iter().map(|n| format!("{}, ", n)).collect::<String>();
+    let dict = format!("{{'descr': '{}', 'fortran_order': False, 'shape': ({:<w$}, {}), }}", self.descr, self.rows, shape.trim_end_matches(", "), w = ROWS_WIDTH);
+    let len = NPY_MAGIC.len() + 2 + dict.len() + 1;
+    format!("{}{}\n", dict, " ".repeat((64 - len % 64) % 64))
+  }
+
+  fn write_header(&mut self) -> Result<(), String> {
+    let dict = self.header_dict();
+    self.file.seek(SeekFrom::Start(0)).map_err(|_| "Cannot seek file")?;
+    self.file.write_all(NPY_MAGIC).map_err(|_| "Cannot write to file")?;
+    self.file.write_all(&(dict.len() as u16).to_le_bytes()).map_err(|_| "Cannot write to file")?;
+    self.file.write_all(dict.as_bytes()).map_err(|_| "Cannot write to file".to_string())
+  }
+
+  /// Append a row, which must hold as many elements as the row shape
+  pub fn write_row(&mut self, row: &[u8]) -> Result<(), String> {
+    if row.len() != self.row_shape.iter().product::<usize>() {
+      return Err("Row does not have the shape of the array".to_string());
+    }
+    self.file.write_all(row).map_err(|_| "Cannot write to file")?;
+    self.rows += 1;
+    Ok(())
+  }
+
+  pub fn rows(&self) -> usize {
+    self.rows
+  }
+
+  /// Drop the rows after the first `rows` ones
+  pub fn truncate(&mut self, rows: usize) -> Result<(), String> {
+    if rows < self.rows {
+      let data_start = NPY_MAGIC.len() + 2 + self.header_dict().len();
+      let row_len = self.row_shape.iter().product::<usize>();
+      self.file.set_len((data_start + rows * row_len) as u64).map_err(|_| "Cannot truncate file")?;
+      self.file.seek(SeekFrom::End(0)).map_err(|_| "Cannot seek file")?;
+      self.rows = rows;
+    }
+    Ok(())
+  }
+
+  /// Write the final number of rows in the header
+  pub fn finish(&mut self) -> Result<(), String> {
+    self.write_header()?;
+    self.file.seek(SeekFrom::End(0)).map_err(|_| "Cannot seek file")?;
+    self.file.flush().map_err(|_| "Cannot flush file".to_string())
+  }
+}
+
+/// The number of rows in a header written by `NpyWriter`
+fn header_rows(header: &str) -> Option<usize> {
+  let start = header.find("'shape': (")? + "'shape': (".len();
+  header.get(start..start + ROWS_WIDTH)?.trim().parse().ok()
+}
+
+/// The `.npy` files written for a dataset, all sharing a path stem: boards
+/// alone go to `{stem}.boards.npy`, while questions with their solutions go to
+/// `{stem}.questions.npy`, `{stem}.answers.npy`, and `{stem}.mask.npy` (true
+/// for the givens of the questions).
+///
+/// Boards are `uint8` arrays of shape `N x n x n`, holding the digits with `0`
+/// for unknown. With one-hot encoding, they are of shape `N x n x n x (n + 1)`
+/// instead, index `0` of the last axis standing for unknown
+pub struct NpyDataset {
+  stem: String,
+  one_hot: bool,
+  append: bool,
+  writers: Option<NpyWriters>,
+}
+
+struct NpyWriters {
+  board_size: usize,
+  boards: NpyWriter,
+  answers: Option<NpyWriter>,
+  mask: Option<NpyWriter>,
+}
+
+impl NpyDataset {
+  /// A dataset written to the files starting with `filename`, without its
+  /// `.npy` extension. The files are created along with the first record
+  pub fn new(filename: &str, one_hot: bool) -> Self {
+    let stem = filename.strip_suffix(".npy").unwrap_or(filename).to_string();
+    Self { stem, one_hot, append: false, writers: None }
+  }
+
+  /// A dataset written before, new records being appended to its files
+  pub fn resume(filename: &str, one_hot: bool) -> Self {
+    Self { append: true, ..Self::new(filename, one_hot) }
+  }
+
+  pub fn set_one_hot(&mut self, one_hot: bool) {
+    self.one_hot = one_hot;
+  }
+
+  /// The files of every array, with the arrays of questions first
+  pub fn filenames(&self, with_solutions: bool) -> Vec<String> {
+    let names : &[&str] = if with_solutions { &["questions", "answers", "mask"] } else { &["boards"] };
+    names.iter().map(|name| format!("{}.{}.npy", self.stem, name)).collect()
+  }
+
+  fn open<B: Board>(&mut self, with_solutions: bool) -> Result<(), String> {
+    let open = if self.append { NpyWriter::append } else { NpyWriter::create };
+    let n = B::size();
+    let board_shape = if self.one_hot { vec![n, n, n + 1] } else { vec![n, n] };
+    let filenames = self.filenames(with_solutions);
+    let mut boards = open(&filenames[0], "|u1", board_shape.clone())?;
+    let (answers, mask) = if with_solutions {
+      let mut answers = open(&filenames[1], "|u1", board_shape)?;
+      let mut mask = open(&filenames[2], "|b1", vec![n, n])?;
+      // An interruption can leave the arrays with different numbers of rows
+      let rows = boards.rows().min(answers.rows()).min(mask.rows());
+      boards.truncate(rows)?;
+      answers.truncate(rows)?;
+      mask.truncate(rows)?;
+      (Some(answers), Some(mask))
+    } else {
+      (None, None)
+    };
+    self.writers = Some(NpyWriters { board_size: n, boards, answers, mask });
+    Ok(())
+  }
+
+  /// Append a board, or a question along with its solution
+  pub fn write_record<B: Board>(&mut self, board: &B, solution: Option<&B>) -> Result<(), String> {
+    if self.writers.is_none() {
+      self.open::<B>(solution.is_some())?;
+    }
+    let one_hot = self.one_hot;
+    let writers = self.writers.as_mut().unwrap();
+    if writers.board_size != B::size() || writers.answers.is_some() != solution.is_some() {
+      return Err("All the records of a npy dataset need to be of the same kind".to_string());
+    }
+    let encode = |board: &B| {
+      let digits = board.to_u8_vec();
+      if one_hot {
+        digits.iter().flat_map(|d| (0..=B::size() as u8).map(move |k| (k == *d) as u8)).collect()
+      } else {
+        digits
+      }
+    };
+    writers.boards.write_row(&encode(board))?;
+    if let (Some(answers), Some(mask), Some(solution)) = (&mut writers.answers, &mut writers.mask, solution) {
+      answers.write_row(&encode(solution))?;
+      mask.write_row(&board.to_u8_vec().iter().map(|d| (*d != 0) as u8).collect::<Vec<_>>())?;
+    }
+    Ok(())
+  }
+
+  /// Write the final number of records in the headers of the files
+  pub fn finish(&mut self) -> Result<(), String> {
+    if let Some(writers) = &mut self.writers {
+      writers.boards.finish()?;
+      if let (Some(answers), Some(mask)) = (&mut writers.answers, &mut writers.mask) {
+        answers.finish()?;
+        mask.finish()?;
+      }
+    }
+    Ok(())
+  }
+}
@@ -1,55 +1,363 @@
-use std::fs::File;
+use std::fs::{File, OpenOptions};
+use std::io::SeekFrom;
 use std::io::prelude::*;
+use std::str::FromStr;
 
-use crate::Board;
+use structopt::StructOpt;
 
-pub enum Output {
+use crate::{Board, LatexRenderer, Renderer, TraceStep, LATEX_DOCUMENT_BEGIN, LATEX_DOCUMENT_END};
+use super::binary::BinaryHeader;
+use super::csv::{csv_row, CSV_HEADER};
+use super::npy::NpyDataset;
+
+// The options of the binaries for `--output-format pretty`, to be flattened
+// into their `StructOpt` options. Not a doc comment, which would replace the
+// about text of the binaries
+#[derive(StructOpt, Clone, Copy, Debug, Default)]
+pub struct RenderOptions {
+  /// With `--output-format pretty`, draw the grids with ASCII characters only
+  #[structopt(long)]
+  pub ascii: bool,
+
+  /// With `--output-format pretty`, show the possible answers of the unknown
+  /// slots as pencil marks
+  #[structopt(long)]
+  pub candidates: bool,
+
+  /// With `--output-format pretty`, color the givens and the solved slots
+  #[structopt(long)]
+  pub color: bool,
+}
+
+impl RenderOptions {
+  pub fn renderer(&self) -> Renderer {
+    Renderer::from_flags(self.ascii, self.candidates, self.color)
+  }
+}
+
+// The output options of the binaries writing boards, to be flattened into
+// their `StructOpt` options like `RenderOptions`
+#[derive(StructOpt, Clone, Copy, Debug, Default)]
+pub struct OutputOptions {
+  /// Output format, either `json`, `ndjson`, `pretty`, `latex`, `bin`, `npy`,
+  /// or `csv`. With `ndjson` every record is written on its own line and
+  /// flushed right away. With `pretty` the boards are drawn as grids, and with
+  /// `latex` as TikZ pictures. `bin` is a compact binary format, `npy` writes
+  /// NumPy arrays of the questions, answers and givens next to the output file,
+  /// and `csv` writes `id,question,answer,clues,difficulty` rows
+  #[structopt(long, default_value = "json", name = "FORMAT")]
+  pub output_format: OutputFormat,
+
+  /// With `--output-format npy`, one-hot encode the digits, giving arrays of
+  /// shape N x 9 x 9 x 10 for 9x9 boards (index 0 standing for unknown)
+  #[structopt(long)]
+  pub npy_one_hot: bool,
+
+  /// With `--output-format csv`, fill in the difficulty column, which needs
+  /// every question to be solved again
+  #[structopt(long)]
+  pub csv_difficulty: bool,
+
+  /// With `--output-format latex`, write a complete LaTeX document instead of
+  /// one picture per record
+  #[structopt(long)]
+  pub latex_document: bool,
+}
+
+impl OutputOptions {
+  /// Create the output to a file, or on screen when no file is given
+  pub fn output(&self, maybe_file: &Option<String>) -> Result<Output, String> {
+    Ok(self.configure(Output::new_with_format(maybe_file, self.output_format)?))
+  }
+
+  /// Reopen the output of an interrupted run, see `Output::resume`
+  pub fn resume(&self, maybe_file: &Option<String>) -> Result<Output, String> {
+    Ok(self.configure(Output::resume(maybe_file, self.output_format)?))
+  }
+
+  fn configure(&self, output: Output) -> Output {
+    output.with_npy_one_hot(self.npy_one_hot).with_csv_difficulty(self.csv_difficulty).with_latex_document(self.latex_document)
+  }
+}
+
+/// The format used when writing boards to the output
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+  /// A single json array containing all the records. Printed in a human
+  /// readable form when writing to stdout
+  #[default]
+  Json,
+
+  /// Newline delimited json: every record is a self-contained json value on
+  /// its own line, flushed as soon as it is produced
+  NdJson,
+
+  /// Text grids showing the block boundaries, for reading by humans. See
+  /// `Renderer`
+  Pretty,
+
+  /// TikZ pictures for LaTeX documents, either as fragments to be included in
+  /// a document, or as a complete document (see `Output::with_latex_document`)
+  Latex,
+
+  /// The compact binary format described in `BinaryHeader`, for very large
+  /// datasets. The header is written along with the first record
+  Binary,
+
+  /// NumPy `.npy` arrays of the boards, see `NpyDataset`. Needs an output file
+  Npy,
+
+  /// CSV rows for spreadsheets, after a `CSV_HEADER` line. See `csv_row`
+  Csv,
+}
+
+impl FromStr for OutputFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "json" => Ok(Self::Json),
+      "ndjson" | "jsonl" => Ok(Self::NdJson),
+      "pretty" => Ok(Self::Pretty),
+      "latex" | "tex" => Ok(Self::Latex),
+      "bin" | "binary" => Ok(Self::Binary),
+      "npy" => Ok(Self::Npy),
+      "csv" => Ok(Self::Csv),
+      _ => Err(format!("Unknown output format {}", s)),
+    }
+  }
+}
+
+pub enum OutputTarget {
   File(File),
   Stdout,
+  Npy(Box<NpyDataset>),
+}
+
+pub struct Output {
+  target: OutputTarget,
+  format: OutputFormat,
+  renderer: Renderer,
+  latex_document: bool,
+  binary_header: Option<BinaryHeader>,
+  /// Number of CSV rows written, which gives the id of the next row
+  csv_rows: usize,
+  csv_difficulty: bool,
+  resumed: bool,
 }
 
 impl Output {
+  /// Write json to a file, or print it on screen when no file is given
   pub fn new(maybe_file: &Option<String>) -> Result<Self, String> {
-    match maybe_file {
+    Self::new_with_format(maybe_file, OutputFormat::Json)
+  }
+
+  pub fn new_with_format(maybe_file: &Option<String>, format: OutputFormat) -> Result<Self, String> {
+    let target = match maybe_file {
+      Some(filename) if format == OutputFormat::Npy => {
+        OutputTarget::Npy(Box::new(NpyDataset::new(filename, false)))
+      },
       Some(filename) => {
         let file = File::create(filename).map_err(|_| "Cannot create file")?;
-        Ok(Self::File(file))
+        OutputTarget::File(file)
+      },
+      None if format == OutputFormat::Npy => {
+        return Err("The npy format needs an output file".to_string());
+      },
+      None => {
+        OutputTarget::Stdout
+      }
+    };
+    Ok(Self { target, format, renderer: Renderer::default(), latex_document: false, binary_header: None, csv_rows: 0, csv_difficulty: false, resumed: false })
+  }
+
+  /// Reopen the output of an interrupted run so that new records are appended
+  /// to it. For the json format, the closing bracket of the array is removed
+  /// and no opening bracket is written again
+  pub fn resume(maybe_file: &Option<String>, format: OutputFormat) -> Result<Self, String> {
+    let mut binary_header = None;
+    let mut csv_rows = 0;
+    let target = match maybe_file {
+      Some(filename) if format == OutputFormat::Npy => {
+        OutputTarget::Npy(Box::new(NpyDataset::resume(filename, false)))
+      },
+      Some(filename) => {
+        let mut file = OpenOptions::new().read(true).write(true).open(filename).map_err(|_| "Cannot open file")?;
+        let len = file.seek(SeekFrom::End(0)).map_err(|_| "Cannot seek file")?;
+        let tail_len = len.min(64);
+        let mut tail = vec![0; tail_len as usize];
+        file.seek(SeekFrom::End(-(tail_len as i64))).map_err(|_| "Cannot seek file")?;
+        file.read_exact(&mut tail).map_err(|_| "Cannot read file")?;
+        let end = match format {
+          OutputFormat::Json => Some(tail.iter().rposition(|c| *c == b']').ok_or("Cannot find the end of the json array in the output file")?),
+          // A complete LaTeX document is ended again when the output finishes
+          OutputFormat::Latex => tail.windows(LATEX_DOCUMENT_END.len()).rposition(|w| w == LATEX_DOCUMENT_END.as_bytes()),
+          _ => None,
+        };
+        if let Some(end) = end {
+          file.set_len(len - tail_len + end as u64).map_err(|_| "Cannot truncate file")?;
+        }
+
+        // New binary records have to follow the header already written
+        if format == OutputFormat::Binary && len > 0 {
+          let mut header = [0; 8];
+          file.seek(SeekFrom::Start(0)).map_err(|_| "Cannot seek file")?;
+          file.read_exact(&mut header).map_err(|_| "Cannot read file")?;
+          binary_header = Some(BinaryHeader::parse(&header)?);
+        }
+
+        // Ids of new CSV rows follow the ones already written
+        if format == OutputFormat::Csv {
+          let mut contents = String::new();
+          file.seek(SeekFrom::Start(0)).map_err(|_| "Cannot seek file")?;
+          file.read_to_string(&mut contents).map_err(|_| "Cannot read file")?;
+          csv_rows = contents.lines().filter(|line| !line.trim().is_empty()).count().saturating_sub(1);
+        }
+        file.seek(SeekFrom::End(0)).map_err(|_| "Cannot seek file")?;
+        OutputTarget::File(file)
+      },
+      None if format == OutputFormat::Npy => {
+        return Err("The npy format needs an output file".to_string());
       },
       None => {
-        Ok(Self::Stdout)
+        OutputTarget::Stdout
       }
+    };
+    Ok(Self { target, format, renderer: Renderer::default(), latex_document: false, binary_header, csv_rows, csv_difficulty: false, resumed: true })
+  }
+
+  pub fn format(&self) -> OutputFormat {
+    self.format
+  }
+
+  /// Set the renderer used by the pretty format
+  pub fn with_renderer(mut self, renderer: Renderer) -> Self {
+    self.renderer = renderer;
+    self
+  }
+
+  /// With the npy format, one-hot encode the digits of the boards
+  pub fn with_npy_one_hot(mut self, one_hot: bool) -> Self {
+    if let OutputTarget::Npy(dataset) = &mut self.target {
+      dataset.set_one_hot(one_hot);
     }
+    self
+  }
+
+  /// With the csv format, fill in the difficulty column, which solves every
+  /// question written
+  pub fn with_csv_difficulty(mut self, difficulty: bool) -> Self {
+    self.csv_difficulty = difficulty;
+    self
+  }
+
+  /// With the latex format, write a complete document instead of fragments
+  pub fn with_latex_document(mut self, latex_document: bool) -> Self {
+    self.latex_document = latex_document;
+    self
   }
 
   pub fn is_file(&self) -> bool {
-    match self {
-      Self::File(_) => true,
-      _ => false,
-    }
+    matches!(self.target, OutputTarget::File(_))
   }
 
   pub fn write(&mut self, s: &str) -> Result<(), String> {
-    match self {
-      Self::File(file) => {
+    match &mut self.target {
+      OutputTarget::File(file) => {
         file.write_all(s.as_bytes()).map_err(|_| "Cannot write to file".to_string())
       },
-      _ => {
+      OutputTarget::Npy(_) => Err("Only boards can be written in the npy format".to_string()),
+      OutputTarget::Stdout => {
         println!("{}", s);
         Ok(())
       }
     }
   }
 
+  /// Write a single line and flush it, so that the line is visible to readers
+  /// of the output right away
+  pub fn write_line(&mut self, s: &str) -> Result<(), String> {
+    match &mut self.target {
+      OutputTarget::File(file) => {
+        file.write_all(s.as_bytes()).map_err(|_| "Cannot write to file")?;
+        file.write_all(b"\n").map_err(|_| "Cannot write to file")?;
+        file.flush().map_err(|_| "Cannot flush file".to_string())
+      },
+      OutputTarget::Npy(_) => Err("Only boards can be written in the npy format".to_string()),
+      OutputTarget::Stdout => {
+        let stdout = std::io::stdout();
+        let mut handle = stdout.lock();
+        writeln!(handle, "{}", s).map_err(|_| "Cannot write to stdout")?;
+        handle.flush().map_err(|_| "Cannot flush stdout".to_string())
+      }
+    }
+  }
+
+  /// Write raw bytes, for the binary format
+  pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+    match &mut self.target {
+      OutputTarget::File(file) => {
+        file.write_all(bytes).map_err(|_| "Cannot write to file".to_string())
+      },
+      OutputTarget::Npy(_) => Err("Only boards can be written in the npy format".to_string()),
+      OutputTarget::Stdout => {
+        let stdout = std::io::stdout();
+        let mut handle = stdout.lock();
+        handle.write_all(bytes).map_err(|_| "Cannot write to stdout".to_string())
+      }
+    }
+  }
+
+  /// Write a binary record, after the header if this is the first record
+  fn write_binary_record(&mut self, board_size: usize, question: &[u8], answer: Option<&[u8]>) -> Result<(), String> {
+    let header = match self.binary_header {
+      Some(header) => header,
+      None => {
+        let header = BinaryHeader::new(board_size, answer.is_some());
+        self.write_bytes(&header.to_bytes())?;
+        self.binary_header = Some(header);
+        header
+      },
+    };
+    self.write_bytes(&header.encode_record(question, answer)?)
+  }
+
+  fn write_csv_row<B: Board>(&mut self, question: &B, answer: Option<&B>) -> Result<(), String> {
+    self.csv_rows += 1;
+    let difficulty = if self.csv_difficulty { Some(question.difficulty()) } else { None };
+    let row = csv_row(self.csv_rows, question, answer, difficulty);
+    self.write_line(&row)
+  }
+
+  fn write_npy_record<B: Board>(&mut self, board: &B, solution: Option<&B>) -> Result<(), String> {
+    match &mut self.target {
+      OutputTarget::Npy(dataset) => dataset.write_record(board, solution),
+      _ => Err("The npy format needs an output file".to_string()),
+    }
+  }
+
+  fn is_json_file(&self) -> bool {
+    self.is_file() && self.format == OutputFormat::Json
+  }
+
+  fn is_latex_document(&self) -> bool {
+    self.format == OutputFormat::Latex && self.latex_document
+  }
+
   pub fn output_init(&mut self) -> Result<(), String> {
-    if self.is_file() {
+    if self.is_json_file() && !self.resumed {
       self.write("[")
+    } else if self.is_latex_document() && !self.resumed {
+      self.write_line(LATEX_DOCUMENT_BEGIN.trim_end())
+    } else if self.format == OutputFormat::Csv && !self.resumed {
+      self.write_line(CSV_HEADER)
     } else {
       Ok(())
     }
   }
 
   pub fn output_separator(&mut self) -> Result<(), String> {
-    if self.is_file() {
+    if self.is_json_file() {
       self.write(",")
     } else {
       Ok(())
@@ -57,8 +365,12 @@ impl Output {
   }
 
   pub fn output_finish(&mut self) -> Result<(), String> {
-    if self.is_file() {
+    if let OutputTarget::Npy(dataset) = &mut self.target {
+      dataset.finish()
+    } else if self.is_json_file() {
       self.write("]")
+    } else if self.is_latex_document() {
+      self.write_line(LATEX_DOCUMENT_END.trim_end())
     } else {
       Ok(())
     }
@@ -79,26 +391,84 @@ impl Output {
     s
   }
 
+  pub fn board_with_solution_to_json_str<B: Board>(board: &B, solution: &B) -> String {
+    format!("{{\"q\":{},\"a\":{}}}", Self::board_to_json_str(board), Self::board_to_json_str(solution))
+  }
+
+  /// The steps of a trace as `[row, column, value, reason]` arrays
+  pub fn trace_to_json_str(steps: &[TraceStep]) -> String {
+    let steps = steps.iter().map(|step| {
+      format!("[{},{},{},\"{}\"]", step.slot.0, step.slot.1, step.value, step.reason)
+    }).collect::<Vec<_>>();
+    format!("[{}]", steps.join(","))
+  }
+
+  /// Output a question with its solution and the steps solving it, as a json
+  /// record with an extra `"t"` field. Only the json and ndjson formats can
+  /// hold traces
+  pub fn output_board_with_trace<B: Board>(&mut self, board: &B, solution: &B, steps: &[TraceStep]) -> Result<(), String> {
+    let record = format!("{{\"q\":{},\"a\":{},\"t\":{}}}",
+      Self::board_to_json_str(board), Self::board_to_json_str(solution), Self::trace_to_json_str(steps));
+    match self.format {
+      OutputFormat::NdJson => self.write_line(&record),
+      OutputFormat::Json if self.is_file() => self.write(&record),
+      OutputFormat::Json => {
+        self.output_board_with_solution(board, solution)?;
+        self.write("Trace:")?;
+        let steps = steps.iter().map(ToString::to_string).collect::<Vec<_>>();
+        self.write(&steps.join("\n"))
+      },
+      _ => Err("Traces can only be written in the json and ndjson formats".to_string()),
+    }
+  }
+
   pub fn output_board<B: Board>(&mut self, board: &B) -> Result<(), String> {
-    if self.is_file() {
-      self.write(&Self::board_to_json_str(board))
-    } else {
-      self.write(&board.to_string())
+    match self.format {
+      OutputFormat::NdJson => self.write_line(&Self::board_to_json_str(board)),
+      OutputFormat::Pretty => self.write_line(&self.renderer.render(board)),
+      OutputFormat::Binary => self.write_binary_record(B::size(), &board.to_u8_vec(), None),
+      OutputFormat::Npy => self.write_npy_record(board, None),
+      OutputFormat::Csv => self.write_csv_row(board, None),
+      OutputFormat::Latex => {
+        let grid = LatexRenderer::new().render(board);
+        self.write_line(&format!("\\begin{{center}}\n{}\n\\end{{center}}", grid))
+      },
+      OutputFormat::Json => {
+        if self.is_file() {
+          self.write(&Self::board_to_json_str(board))
+        } else {
+          self.write(&board.to_string())
+        }
+      }
     }
   }
 
   pub fn output_board_with_solution<B: Board>(&mut self, board: &B, solution: &B) -> Result<(), String> {
-    if self.is_file() {
-      self.write("{\"q\":")?;
-      self.write(&Self::board_to_json_str(board))?;
-      self.write(",\"a\":")?;
-      self.write(&Self::board_to_json_str(solution))?;
-      self.write("}")
-    } else {
-      self.write("Question: ")?;
-      self.write(&board.to_string())?;
-      self.write("Solution: ")?;
-      self.write(&solution.to_string())
+    match self.format {
+      OutputFormat::NdJson => self.write_line(&Self::board_with_solution_to_json_str(board, solution)),
+      OutputFormat::Pretty => {
+        let question = self.renderer.render(board);
+        let solution = self.renderer.render_solution(board, solution);
+        self.write_line(&format!("Question:\n{}Solution:\n{}", question, solution))
+      },
+      OutputFormat::Binary => self.write_binary_record(B::size(), &board.to_u8_vec(), Some(&solution.to_u8_vec())),
+      OutputFormat::Npy => self.write_npy_record(board, Some(solution)),
+      OutputFormat::Csv => self.write_csv_row(board, Some(solution)),
+      OutputFormat::Latex => {
+        let renderer = LatexRenderer::new();
+        let (question, answer) = (renderer.render(board), renderer.render_solution(board, solution));
+        self.write_line(&format!("\\begin{{center}}\n{}\n\\qquad\n{}\n\\end{{center}}", question, answer))
+      },
+      OutputFormat::Json => {
+        if self.is_file() {
+          self.write(&Self::board_with_solution_to_json_str(board, solution))
+        } else {
+          self.write("Question: ")?;
+          self.write(&board.to_string())?;
+          self.write("Solution: ")?;
+          self.write(&solution.to_string())
+        }
+      }
     }
   }
-}
\ No newline at end of file
+}
@@ -0,0 +1,767 @@
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use crate::board::*;
+use crate::cnf::Cnf;
+use crate::heuristic::*;
+#[cfg(feature = "sat")]
+use crate::sat::SatSolver;
+
+/// One of the two diagonals of a board
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Diagonal {
+  /// From the bottom left to the top right slot
+  Positive,
+
+  /// From the top left to the bottom right slot
+  Negative,
+}
+
+/// A rule of a variant sudoku, on top of the rows and columns
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Constraint {
+  /// Every digit appears once on the diagonal
+  Diagonal(Diagonal),
+
+  /// Irregular regions taking the place of the blocks, every digit appearing
+  /// once in each of them
+  Regions(Vec<Vec<Slot>>),
+
+  /// Digits do not repeat inside the cage, and add up to the sum if any
+  Cage { slots: Vec<Slot>, sum: Option<u32> },
+
+  /// Digits strictly increase along the thermometer, starting from the bulb
+  Thermometer(Vec<Slot>),
+
+  /// Digits along the arrow add up to the number in the circle. A circle
+  /// spanning several slots holds the number read from its digits in order
+  Arrow { circle: Vec<Slot>, arrow: Vec<Slot> },
+}
+
+impl Constraint {
+  /// Check that the known digits of a board (row major, `0` for unknown) can
+  /// still satisfy the constraint
+  fn allows(&self, digits: &[u8], size: usize) -> bool {
+    let get = |slot: &Slot| digits[slot.0 * size + slot.1];
+    match self {
+      Constraint::Diagonal(diagonal) => all_different(diagonal_slots(*diagonal, size).iter().map(get)),
+      Constraint::Regions(regions) => regions.iter().all(|region| all_different(region.iter().map(get))),
+      Constraint::Cage { slots, sum } => {
+        if !all_different(slots.iter().map(get)) {
+          return false;
+        }
+        let sum = match sum {
+          Some(sum) => *sum,
+          None => return true,
+        };
+
+        // The unknown digits are the smallest or the largest digits left
+        let known = slots.iter().map(get).filter(|d| *d != 0).collect::<Vec<_>>();
+        let left = (1..=size as u32).filter(|d| !known.contains(&(*d as u8))).collect::<Vec<_>>();
+        let num_unknowns = slots.len() - known.len();
+        if num_unknowns > left.len() {
+          return false;
+        }
+        let known_sum = known.iter().map(|d| *d as u32).sum::<u32>();
+        let min = known_sum + left[..num_unknowns].iter().sum::<u32>();
+        let max = known_sum + left[left.len() - num_unknowns..].iter().sum::<u32>();
+        min <= sum && sum <= max
+      },
+      Constraint::Thermometer(slots) => {
+        let len = slots.len();
+        let known = slots.iter().map(get).enumerate().filter(|(_, d)| *d != 0).collect::<Vec<_>>();
+        known.iter().all(|(i, d)| *d as usize > *i && (*d as usize) + (len - 1 - i) <= size)
+          && known.windows(2).all(|pair| pair[1].1 as usize >= pair[0].1 as usize + (pair[1].0 - pair[0].0))
+      },
+      Constraint::Arrow { circle, arrow } => {
+        // Bounds of the circle, unknown digits being 1 at least and `size` at most
+        let number = |unknown: u64| circle.iter().map(get).fold(0u64, |n, d| n * 10 + if d == 0 { unknown } else { d as u64 });
+        let (circle_min, circle_max) = (number(1), number(size as u64));
+        let known_sum = arrow.iter().map(|slot| get(slot) as u64).sum::<u64>();
+        let num_unknowns = arrow.iter().filter(|slot| get(slot) == 0).count() as u64;
+        known_sum + num_unknowns <= circle_max && circle_min <= known_sum + num_unknowns * size as u64
+      },
+    }
+  }
+
+  /// All the slots the constraint is about
+  fn slots(&self, size: usize) -> Vec<Slot> {
+    match self {
+      Constraint::Diagonal(diagonal) => diagonal_slots(*diagonal, size),
+      Constraint::Regions(regions) => regions.concat(),
+      Constraint::Cage { slots, .. } | Constraint::Thermometer(slots) => slots.clone(),
+      Constraint::Arrow { circle, arrow } => circle.iter().chain(arrow).copied().collect(),
+    }
+  }
+
+  /// Add the clauses of the constraint to a CNF encoding, apart from the units
+  /// already encoded (see `VariantPuzzle::units`)
+  fn encode<B: Board>(&self, cnf: &mut Cnf) {
+    let size = B::size() as u8;
+    let var = |slot: &Slot, d: u8| Cnf::variable::<B>(slot, d);
+    match self {
+      Constraint::Diagonal(_) | Constraint::Regions(_) => {},
+      Constraint::Cage { slots, sum } => {
+        for d in 1..=size {
+          cnf.add_at_most_one(&slots.iter().map(|slot| var(slot, d)).collect::<Vec<_>>());
+        }
+
+        // One of the sets of digits adding up to the sum is picked, and each of
+        // its digits appears in the cage
+        if let Some(sum) = sum {
+          let sets = digit_sets(size, slots.len(), *sum);
+          let set_vars = sets.iter().map(|_| cnf.new_variable()).collect::<Vec<_>>();
+          cnf.clauses.push(set_vars.clone());
+          for (set, set_var) in sets.iter().zip(&set_vars) {
+            for d in set {
+              cnf.clauses.push(std::iter::once(-set_var).chain(slots.iter().map(|slot| var(slot, *d))).collect());
+            }
+          }
+        }
+      },
+      Constraint::Thermometer(slots) => {
+        for pair in slots.windows(2) {
+          for d in 1..=size {
+            cnf.clauses.push(std::iter::once(-var(&pair[0], d)).chain((d + 1..=size).map(|e| var(&pair[1], e))).collect());
+          }
+        }
+      },
+      Constraint::Arrow { circle, arrow } => {
+        // `sums[v]` tells that the digits of the arrow so far add up to `v`.
+        // Every true sum implies the next ones, and only one final sum can be
+        // true, which rules out the sums not coming from the digits
+        let mut sums = match arrow.first() {
+          Some(slot) => std::iter::once(None).chain((1..=size).map(|d| Some(var(slot, d)))).collect::<Vec<_>>(),
+          None => vec![],
+        };
+        for slot in arrow.iter().skip(1) {
+          let mut next = vec![None; sums.len() + size as usize];
+          for (v, sum_var) in sums.iter().enumerate().filter_map(|(v, sum_var)| sum_var.map(|sum_var| (v, sum_var))) {
+            for d in 1..=size {
+              let next_var = *next[v + d as usize].get_or_insert_with(|| cnf.new_variable());
+              cnf.clauses.push(vec![-sum_var, -var(slot, d), next_var]);
+            }
+          }
+          sums = next;
+        }
+        cnf.add_at_most_one(&sums.iter().flatten().copied().collect::<Vec<_>>());
+
+        // Every number in the circle needs the same sum along the arrow
+        for k in 0..(size as usize).pow(circle.len() as u32) {
+          let digits = (0..circle.len()).rev().map(|i| (k / (size as usize).pow(i as u32) % size as usize + 1) as u8).collect::<Vec<_>>();
+          let number = digits.iter().fold(0, |n, d| n * 10 + *d as usize);
+          let mut clause = circle.iter().zip(&digits).map(|(slot, d)| -var(slot, *d)).collect::<Vec<_>>();
+          if let Some(Some(sum_var)) = sums.get(number) {
+            clause.push(*sum_var);
+          }
+          cnf.clauses.push(clause);
+        }
+      },
+    }
+  }
+}
+
+/// The sets of `len` different digits from 1 to `size` adding up to `sum`, each
+/// in increasing order
+fn digit_sets(size: u8, len: usize, sum: u32) -> Vec<Vec<u8>> {
+  fn extend(set: &mut Vec<u8>, next: u8, size: u8, len: usize, sum: u32, sets: &mut Vec<Vec<u8>>) {
+    let total = set.iter().map(|d| *d as u32).sum::<u32>();
+    if set.len() == len {
+      if total == sum {
+        sets.push(set.clone());
+      }
+      return;
+    }
+    for d in next..=size {
+      if total + d as u32 > sum {
+        break;
+      }
+      set.push(d);
+      extend(set, d + 1, size, len, sum, sets);
+      set.pop();
+    }
+  }
+  let mut sets = vec![];
+  extend(&mut vec![], 1, size, len, sum, &mut sets);
+  sets
+}
+
+/// Keep the candidates of the unknown slots of a cage (by index in row major
+/// order) that are part of a set of digits adding up to the sum, the other
+/// unknown slots taking the rest of the set. Returns `false` when a slot has
+/// no candidate left
+fn prune_cage(slots: &[usize], sum: u32, digits: &[u8], candidates: &mut [Vec<u8>], size: usize) -> bool {
+  let known = slots.iter().map(|k| digits[*k]).filter(|d| *d != 0).collect::<Vec<_>>();
+  let unknowns = slots.iter().copied().filter(|k| digits[*k] == 0).collect::<Vec<_>>();
+  if unknowns.is_empty() {
+    return true;
+  }
+  let mut kept = vec![0u64; unknowns.len()];
+  for set in digit_sets(size as u8, slots.len(), sum) {
+    if !known.iter().all(|d| set.contains(d)) {
+      continue;
+    }
+    let rest = set.iter().fold(0u64, |mask, d| if known.contains(d) { mask } else { mask | 1 << d });
+    let masks = unknowns.iter().map(|k| candidates[*k].iter().fold(0u64, |mask, d| mask | 1 << d) & rest).collect::<Vec<_>>();
+    for (i, mask) in masks.iter().enumerate() {
+      for d in (1..=size).filter(|d| mask >> d & 1 == 1) {
+        if kept[i] >> d & 1 == 1 {
+          continue;
+        }
+        let others = masks.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, mask)| mask & !(1 << d)).collect::<Vec<_>>();
+        if can_assign(&others, 0) {
+          kept[i] |= 1 << d;
+        }
+      }
+    }
+  }
+  for (k, mask) in unknowns.iter().zip(kept) {
+    candidates[*k].retain(|d| mask >> d & 1 == 1);
+    if candidates[*k].is_empty() {
+      return false;
+    }
+  }
+  true
+}
+
+/// Keep the candidates of the unknown slots of a thermometer (by index in row
+/// major order) that are above the smallest possible digit of the slot before,
+/// and below the largest possible digit of the slot after. Returns `false`
+/// when a slot has no candidate left
+fn prune_thermometer(slots: &[usize], digits: &[u8], candidates: &mut [Vec<u8>]) -> bool {
+  let possible = |k: usize, candidates: &[Vec<u8>]| if digits[k] != 0 { vec![digits[k]] } else { candidates[k].clone() };
+  let mut lower = 0;
+  for k in slots {
+    candidates[*k].retain(|d| *d > lower);
+    match possible(*k, candidates).first() {
+      Some(d) if *d > lower => lower = *d,
+      _ => return false,
+    }
+  }
+  let mut upper = u8::MAX;
+  for k in slots.iter().rev() {
+    candidates[*k].retain(|d| *d < upper);
+    match possible(*k, candidates).last() {
+      Some(d) if *d < upper => upper = *d,
+      _ => return false,
+    }
+  }
+  true
+}
+
+/// Keep the candidates of the slots of an arrow (by index in row major order)
+/// for which the number in the circle and the sum along the arrow can still
+/// meet, the other slots holding their smallest or largest possible digit.
+/// `groups` lists the positions along the arrow of slots sharing a unit,
+/// whose digits are all different. Returns `false` when a slot has no
+/// candidate left
+fn prune_arrow(circle: &[usize], arrow: &[usize], groups: &[Vec<usize>], digits: &[u8], candidates: &mut [Vec<u8>]) -> bool {
+  let possible = |k: usize, candidates: &[Vec<u8>]| if digits[k] != 0 { vec![digits[k]] } else { candidates[k].clone() };
+
+  // The number in the circle, the digit at position `i` being `d` if any
+  let number = |candidates: &[Vec<u8>], i: Option<usize>, d: u8, largest: bool| circle.iter().enumerate().fold(0u64, |n, (j, k)| {
+    let possible = if Some(j) == i { vec![d] } else { possible(*k, candidates) };
+    n * 10 + if largest { possible.last() } else { possible.first() }.map_or(0, |d| *d as u64)
+  });
+
+  // The smallest or largest sum along the arrow, `None` when a group of slots
+  // cannot get different digits
+  let sum = |candidates: &[Vec<u8>], i: Option<usize>, d: u8, largest: bool| groups.iter().map(|group| {
+    let possible = group.iter().map(|j| if Some(*j) == i { vec![d] } else { possible(arrow[*j], candidates) }).collect::<Vec<_>>();
+    let mut union = possible.concat();
+    union.sort_unstable();
+    union.dedup();
+    if largest { union.reverse() }
+    if possible.iter().any(Vec::is_empty) || union.len() < group.len() {
+      return None;
+    }
+    let each = possible.iter().map(|p| *if largest { p.last() } else { p.first() }.unwrap() as u64).sum::<u64>();
+    let different = union[..group.len()].iter().map(|d| *d as u64).sum::<u64>();
+    Some(if largest { each.min(different) } else { each.max(different) })
+  }).sum::<Option<u64>>();
+
+  let meet = |circle_min: u64, circle_max: u64, sum_min: Option<u64>, sum_max: Option<u64>| match (sum_min, sum_max) {
+    (Some(sum_min), Some(sum_max)) => circle_min <= sum_max && sum_min <= circle_max,
+    _ => false,
+  };
+  for (i, k) in circle.iter().enumerate().filter(|(_, k)| digits[**k] == 0) {
+    let kept = candidates[*k].iter().copied().filter(|d| {
+      meet(number(candidates, Some(i), *d, false), number(candidates, Some(i), *d, true), sum(candidates, None, 0, false), sum(candidates, None, 0, true))
+    }).collect();
+    candidates[*k] = kept;
+  }
+  for (i, k) in arrow.iter().enumerate().filter(|(_, k)| digits[**k] == 0) {
+    let kept = candidates[*k].iter().copied().filter(|d| {
+      meet(number(candidates, None, 0, false), number(candidates, None, 0, true), sum(candidates, Some(i), *d, false), sum(candidates, Some(i), *d, true))
+    }).collect();
+    candidates[*k] = kept;
+  }
+  circle.iter().chain(arrow).all(|k| digits[*k] != 0 || !candidates[*k].is_empty())
+}
+
+/// Check that every slot gets a different digit out of its mask, apart from
+/// the digits of `used`
+fn can_assign(masks: &[u64], used: u64) -> bool {
+  match masks.split_first() {
+    None => true,
+    Some((mask, rest)) => (1..64).any(|d| (mask & !used) >> d & 1 == 1 && can_assign(rest, used | 1 << d)),
+  }
+}
+
+fn diagonal_slots(diagonal: Diagonal, size: usize) -> Vec<Slot> {
+  match diagonal {
+    Diagonal::Positive => (0..size).map(|i| (i, size - 1 - i)).collect(),
+    Diagonal::Negative => (0..size).map(|i| (i, i)).collect(),
+  }
+}
+
+/// Check that the known digits are all different
+fn all_different<I: Iterator<Item = u8>>(digits: I) -> bool {
+  let mut seen = 0u64;
+  for d in digits.filter(|d| *d != 0) {
+    if seen >> d & 1 == 1 {
+      return false;
+    }
+    seen |= 1 << d;
+  }
+  true
+}
+
+/// A variant sudoku: the givens on a board along with extra constraints.
+/// Variant puzzles are solved with a `SolvingContext` like boards, either by
+/// a search checking the constraints on every placement, or by the SAT
+/// backend on the encoding of `to_cnf`.
+///
+/// ```
+/// # use sudoku_rs::*;
+/// // A thermometer along the first row fixes it to 1 2 3 4
+/// let puzzle = VariantPuzzle::new(Board4x4::default())
+///   .constraint(Constraint::Thermometer(vec![(0, 0), (0, 1), (0, 2), (0, 3)]));
+/// let solution = puzzle.solve().next().unwrap();
+/// assert_eq!(solution.to_u8_vec()[..4], [1, 2, 3, 4]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariantPuzzle<B> where B : Board {
+  pub board: B,
+  pub constraints: Vec<Constraint>,
+}
+
+impl<B> VariantPuzzle<B> where B : Board {
+  pub fn new(board: B) -> Self {
+    Self { board, constraints: vec![] }
+  }
+
+  pub fn constraint(mut self, constraint: Constraint) -> Self {
+    self.constraints.push(constraint);
+    self
+  }
+
+  /// The groups of slots where digits do not repeat: rows, columns, the
+  /// blocks unless irregular regions replace them, and diagonals
+  fn units(&self) -> Vec<Vec<Slot>> {
+    let size = B::size();
+    let regions = self.constraints.iter().any(|constraint| matches!(constraint, Constraint::Regions(_)));
+    let mut units = vec![];
+    for k in 0..size {
+      units.push(B::row_slots(k).collect());
+      units.push(B::column_slots(k).collect());
+      if !regions {
+        let begin = (k / B::block_size() * B::block_size(), k % B::block_size() * B::block_size());
+        units.push(B::block_slots(&begin).collect());
+      }
+    }
+    for constraint in &self.constraints {
+      match constraint {
+        Constraint::Regions(regions) => units.extend(regions.iter().cloned()),
+        Constraint::Diagonal(diagonal) => units.push(diagonal_slots(*diagonal, size)),
+        _ => {}
+      }
+    }
+    units
+  }
+
+  /// Check that the known slots of a board break none of the rules
+  pub fn is_valid(&self, board: &B) -> bool {
+    let digits = board.to_u8_vec();
+    let get = |slot: &Slot| digits[slot.0 * B::size() + slot.1];
+    self.units().iter().all(|unit| all_different(unit.iter().map(get)))
+      && self.constraints.iter().all(|constraint| constraint.allows(&digits, B::size()))
+  }
+
+  /// Encode the puzzle as a boolean formula in conjunctive normal form. The
+  /// variables of `Cnf::variable` come first, followed by the ones needed for
+  /// the sums of cages and arrows
+  pub fn to_cnf(&self) -> Cnf {
+    let mut cnf = Cnf::encode_units(&self.board, &self.units());
+    for constraint in &self.constraints {
+      constraint.encode::<B>(&mut cnf);
+    }
+    cnf
+  }
+
+  /// Solve the puzzle with a deterministic search, see `solve_with_ctx`
+  pub fn solve(&self) -> VariantSolutions<B> {
+    self.solve_with_ctx(SolvingContext::deterministic())
+  }
+
+  /// Solve the puzzle by returning a solutions iterator. The search fills in
+  /// the slots with a single possible digit and the digits with a single
+  /// place left in a unit, and branches as told by the heuristic of `ctx`.
+  /// The SAT backend also adds the constraints of its `CnfOptions`
+  pub fn solve_with_ctx(&self, ctx: SolvingContext) -> VariantSolutions<B> {
+    let size = B::size();
+    let units = self.units();
+    let index = |slot: &Slot| slot.0 * size + slot.1;
+    let mut peers = vec![vec![]; size * size];
+    for unit in &units {
+      for a in unit {
+        peers[index(a)].extend(unit.iter().filter(|b| *b != a).map(index));
+      }
+    }
+    let mut constraints_of = vec![vec![]; size * size];
+    for (k, constraint) in self.constraints.iter().enumerate() {
+      for slot in constraint.slots(size) {
+        constraints_of[index(&slot)].push(k);
+      }
+    }
+    for list in peers.iter_mut().chain(constraints_of.iter_mut()) {
+      list.sort_unstable();
+      list.dedup();
+    }
+    let stack = if self.is_valid(&self.board) { vec![self.board.to_u8_vec()] } else { vec![] };
+    VariantSolutions {
+      #[cfg(feature = "sat")]
+      sat: self.sat_solver(&ctx),
+      constraints: self.constraints.clone(),
+      units,
+      peers,
+      constraints_of,
+      stack,
+      ctx,
+      stats: SolveStats::default(),
+      status: SearchStatus::InProgress,
+      phantom: PhantomData,
+    }
+  }
+
+  /// The SAT solver loaded with the encoding of the puzzle, when `ctx` asks
+  /// for the SAT backend
+  #[cfg(feature = "sat")]
+  fn sat_solver(&self, ctx: &SolvingContext) -> Option<SatSolver> {
+    let options = match ctx.backend {
+      SolverBackend::Sat(options) => options,
+      SolverBackend::Search => return None,
+    };
+    let puzzle = options.constraints().into_iter().fold(self.clone(), Self::constraint);
+    Some(SatSolver::from_cnf(&puzzle.to_cnf()))
+  }
+}
+
+/// The `SearchNode` view of a variant puzzle being solved, along with the
+/// possible digits of every unknown slot
+struct VariantNode<'a> {
+  size: usize,
+  block_size: usize,
+  digits: &'a [u8],
+  candidates: &'a [Vec<u8>],
+  units: &'a [Vec<Slot>],
+  peers: &'a [Vec<usize>],
+}
+
+impl<'a> SearchNode for VariantNode<'a> {
+  fn size(&self) -> usize {
+    self.size
+  }
+
+  fn block_size(&self) -> usize {
+    self.block_size
+  }
+
+  fn get(&self, slot: &Slot) -> u8 {
+    self.digits[slot.0 * self.size + slot.1]
+  }
+
+  fn num_candidates(&self, slot: &Slot) -> usize {
+    self.candidates[slot.0 * self.size + slot.1].len()
+  }
+
+  fn candidates(&self, slot: &Slot) -> Vec<u8> {
+    self.candidates[slot.0 * self.size + slot.1].clone()
+  }
+
+  fn units(&self) -> Vec<Vec<Slot>> {
+    self.units.to_vec()
+  }
+
+  fn peers(&self, slot: &Slot) -> Vec<Slot> {
+    self.peers[slot.0 * self.size + slot.1].iter().map(|k| (k / self.size, k % self.size)).collect()
+  }
+}
+
+/// Variant solutions iterator. Like `BoardSolutions`, the search stops when
+/// the budget of its `SolvingContext` is exhausted
+pub struct VariantSolutions<B> where B : Board {
+  constraints: Vec<Constraint>,
+  units: Vec<Vec<Slot>>,
+  /// The slots sharing a unit with every slot, by index in row major order
+  peers: Vec<Vec<usize>>,
+  /// The constraints every slot is part of
+  constraints_of: Vec<Vec<usize>>,
+  stack: Vec<Vec<u8>>,
+  ctx: SolvingContext,
+  stats: SolveStats,
+  status: SearchStatus,
+  #[cfg(feature = "sat")]
+  sat: Option<SatSolver>,
+  phantom: PhantomData<B>,
+}
+
+impl<B> VariantSolutions<B> where B : Board {
+  /// Get the statistics of the search done so far
+  pub fn stats(&self) -> &SolveStats {
+    &self.stats
+  }
+
+  /// Get the state of the search. When the iterator returns `None`, this tells
+  /// whether the search is complete or ran out of budget
+  pub fn status(&self) -> SearchStatus {
+    self.status
+  }
+
+  /// Get the next solution. Returns `Ok(None)` when there is no more solution,
+  /// and `Err` when the budget is exhausted before finding the next solution
+  pub fn try_next(&mut self) -> Result<Option<B>, BudgetExhausted> {
+    let start = self.ctx.collect_stats.then(Instant::now);
+    let result = self.next_solution();
+    if let Some(start) = start {
+      self.stats.elapsed += start.elapsed();
+    }
+    self.status = match result {
+      Ok(Some(_)) => SearchStatus::InProgress,
+      Ok(None) => SearchStatus::Complete,
+      Err(reason) => SearchStatus::BudgetExhausted(reason),
+    };
+    result
+  }
+
+  fn next_solution(&mut self) -> Result<Option<B>, BudgetExhausted> {
+    #[cfg(feature = "sat")]
+    if self.sat.is_some() {
+      return self.next_sat_solution();
+    }
+
+    while !self.stack.is_empty() {
+      self.ctx.budget.check(self.stats.nodes)?;
+      let mut digits = self.stack.pop().unwrap();
+
+      // Every board but the first one is a branch taken on a guess
+      if self.stats.nodes > 0 {
+        self.stats.guesses += 1;
+      }
+      self.stats.nodes += 1;
+
+      let num_unknowns = if self.ctx.collect_stats { digits.iter().filter(|d| **d == 0).count() } else { 0 };
+      let candidates = match self.propagate(&mut digits) {
+        Some(candidates) => candidates,
+        None => {
+          self.stats.backtracks += 1;
+          continue;
+        },
+      };
+      if self.ctx.collect_stats {
+        self.stats.forced_placements += num_unknowns - digits.iter().filter(|d| **d == 0).count();
+      }
+
+      // Pick the slot to branch on and order its possible digits
+      let node = VariantNode {
+        size: B::size(),
+        block_size: B::block_size(),
+        digits: &digits,
+        candidates: &candidates,
+        units: &self.units,
+        peers: &self.peers,
+      };
+      match self.ctx.heuristic.select_slot(&node, self.ctx.rng.as_mut()) {
+        Some(slot) => {
+          let k = slot.0 * B::size() + slot.1;
+          let mut values = candidates[k].clone();
+          self.ctx.heuristic.order_values(&node, &slot, &mut values, self.ctx.rng.as_mut());
+
+          // The first digit goes on top of the stack
+          for d in values.into_iter().rev() {
+            let mut next = digits.clone();
+            next[k] = d;
+            self.stack.push(next);
+          }
+          self.stats.max_stack_depth = self.stats.max_stack_depth.max(self.stack.len());
+        },
+        None => {
+          self.stats.solutions += 1;
+          return Ok(Some(B::from_u8_vec(&digits)));
+        },
+      }
+    }
+    Ok(None)
+  }
+
+  /// Fill in the slots with a single possible digit, and the digits with a
+  /// single place left in a unit, until nothing changes. Returns the possible
+  /// digits of every slot (none for the known ones), or `None` when a slot or
+  /// a digit has no place left
+  fn propagate(&self, digits: &mut [u8]) -> Option<Vec<Vec<u8>>> {
+    let size = B::size();
+    loop {
+      let mut candidates = vec![vec![]; digits.len()];
+      let mut modified = false;
+      for k in 0..digits.len() {
+        if digits[k] != 0 {
+          continue;
+        }
+        let slot_candidates = self.candidates(digits, k);
+        match slot_candidates.len() {
+          0 => return None,
+          1 => {
+            digits[k] = slot_candidates[0];
+            modified = true;
+          },
+          _ => candidates[k] = slot_candidates,
+        }
+      }
+      if modified {
+        continue;
+      }
+
+      // The digits of a cage come from the sets adding up to its sum, the
+      // digits of a thermometer leave room for the ones before and after them,
+      // and the number in the circle of an arrow is within reach of its sum
+      for constraint in &self.constraints {
+        let pruned = match constraint {
+          Constraint::Cage { slots, sum: Some(sum) } => {
+            let slots = slots.iter().map(|slot| slot.0 * size + slot.1).collect::<Vec<_>>();
+            prune_cage(&slots, *sum, digits, &mut candidates, size)
+          },
+          Constraint::Thermometer(slots) => {
+            let slots = slots.iter().map(|slot| slot.0 * size + slot.1).collect::<Vec<_>>();
+            prune_thermometer(&slots, digits, &mut candidates)
+          },
+          Constraint::Arrow { circle, arrow } => {
+            let circle = circle.iter().map(|slot| slot.0 * size + slot.1).collect::<Vec<_>>();
+            let arrow = arrow.iter().map(|slot| slot.0 * size + slot.1).collect::<Vec<_>>();
+            let mut groups : Vec<Vec<usize>> = vec![];
+            for (i, k) in arrow.iter().enumerate() {
+              match groups.iter_mut().find(|group| group.iter().all(|j| self.peers[*k].binary_search(&arrow[*j]).is_ok())) {
+                Some(group) => group.push(i),
+                None => groups.push(vec![i]),
+              }
+            }
+            prune_arrow(&circle, &arrow, &groups, digits, &mut candidates)
+          },
+          _ => true,
+        };
+        if !pruned {
+          return None;
+        }
+      }
+      // Placing a digit changes the candidates of its peers, so one at a time
+      if let Some(k) = (0..digits.len()).find(|k| digits[*k] == 0 && candidates[*k].len() == 1) {
+        digits[k] = candidates[k][0];
+        continue;
+      }
+
+      // Every digit appears once in the units covering as many slots as
+      // there are digits
+      let mut hidden_single = None;
+      for unit in self.units.iter().filter(|unit| unit.len() == size) {
+        let mut places = vec![(0, 0); size + 1];
+        for k in unit.iter().map(|slot| slot.0 * size + slot.1) {
+          if digits[k] != 0 {
+            places[digits[k] as usize] = (usize::MAX, k);
+          }
+          for d in &candidates[k] {
+            places[*d as usize] = (places[*d as usize].0.saturating_add(1), k);
+          }
+        }
+        match places[1..].iter().position(|(count, _)| *count <= 1) {
+          Some(_) if places[1..].iter().any(|(count, _)| *count == 0) => return None,
+          Some(d) => {
+            hidden_single = Some((places[d + 1].1, d as u8 + 1));
+            break;
+          },
+          None => {},
+        }
+      }
+      match hidden_single {
+        Some((k, d)) => digits[k] = d,
+        None => return Some(candidates),
+      }
+    }
+  }
+
+  /// The digits that can go in slot `k` without breaking a rule
+  fn candidates(&self, digits: &mut [u8], k: usize) -> Vec<u8> {
+    let size = B::size();
+    let mut result = vec![];
+    for d in 1..=size as u8 {
+      if self.peers[k].iter().any(|peer| digits[*peer] == d) {
+        continue;
+      }
+      digits[k] = d;
+      if self.constraints_of[k].iter().all(|c| self.constraints[*c].allows(digits, size)) {
+        result.push(d);
+      }
+      digits[k] = 0;
+    }
+    result
+  }
+}
+
+#[cfg(feature = "sat")]
+impl<B> VariantSolutions<B> where B : Board {
+  /// Get the next solution from the SAT solver, the first board staying on the
+  /// stack until the search is complete
+  fn next_sat_solution(&mut self) -> Result<Option<B>, BudgetExhausted> {
+    let solver = match (&mut self.sat, self.stack.is_empty()) {
+      (Some(solver), false) => solver,
+      _ => return Ok(None),
+    };
+    if self.stats.nodes == 0 {
+      if let Some(rng) = self.ctx.rng.as_mut() {
+        solver.randomize(rng);
+      }
+    }
+
+    // Decisions of the SAT solver count as nodes of the search
+    let before = solver.stats();
+    let (budget, nodes) = (&self.ctx.budget, self.stats.nodes);
+    let result = solver.solve_with_budget(|decisions| budget.check(nodes + decisions - before.decisions));
+    let after = solver.stats();
+    self.stats.nodes += after.decisions - before.decisions;
+    self.stats.guesses += after.decisions - before.decisions;
+    self.stats.backtracks += after.conflicts - before.conflicts;
+    self.stats.forced_placements += after.propagations - before.propagations;
+    self.stats.max_stack_depth = self.stats.max_stack_depth.max(after.max_level);
+
+    match result? {
+      Some(model) => {
+        let solution = B::from_model(&model).expect("Models of the encoding are complete boards");
+        solver.add_clause(&Cnf::blocking_clause(&solution));
+        self.stats.solutions += 1;
+        Ok(Some(solution))
+      },
+      None => {
+        self.stack.clear();
+        Ok(None)
+      },
+    }
+  }
+}
+
+impl<B> Iterator for VariantSolutions<B> where B : Board {
+  type Item = B;
+
+  /// Returns `None` both when the search is complete and when its budget is
+  /// exhausted, use `status` or `try_next` to tell them apart
+  fn next(&mut self) -> Option<Self::Item> {
+    self.try_next().ok().flatten()
+  }
+}
@@ -0,0 +1,69 @@
+use sudoku_rs::{*, utils::*};
+
+mod common;
+
+fn questions() -> Vec<(Board9x9, Board9x9)> {
+  common::questions(3, 10, 40)
+}
+
+#[test]
+fn binary_round_trip_with_solutions() {
+  let mut writer = BinaryWriter::new(vec![], 9, true).unwrap();
+  for (question, solution) in questions() {
+    writer.write_board_with_solution(&question, &solution).unwrap();
+  }
+  let bytes = writer.into_inner();
+  assert_eq!(bytes.len(), 8 + 10 * 52);
+
+  let mut reader = BinaryReader::new(&bytes[..]).unwrap();
+  assert_eq!(*reader.header(), BinaryHeader::new(9, true));
+  for (question, solution) in questions() {
+    assert_eq!(reader.next_boards::<Board9x9>().unwrap(), Some((question, Some(solution))));
+  }
+  assert_eq!(reader.next_boards::<Board9x9>().unwrap(), None);
+}
+
+#[test]
+fn binary_rejects_invalid_content() {
+  let mut writer = BinaryWriter::new(vec![], 9, true).unwrap();
+  let (question, solution) = &questions()[0];
+  assert!(writer.write_board(question).is_err());
+  // The question has to agree with the solution
+  assert!(writer.write_board_with_solution(solution, question).is_err());
+  writer.write_board_with_solution(question, solution).unwrap();
+
+  let bytes = writer.into_inner();
+  assert!(parse_binary_records(&bytes[..bytes.len() - 1]).is_err());
+  assert!(BinaryReader::new(&bytes[..]).unwrap().next_boards::<Board4x4>().is_err());
+  // Digits larger than the board size
+  let mut corrupt = bytes.clone();
+  corrupt[8] = 0xff;
+  assert!(parse_binary_records(&corrupt).is_err());
+  assert!(BinaryReader::new(&corrupt[..]).unwrap().next_boards::<Board9x9>().is_err());
+  let mut future = bytes.clone();
+  future[4] = BINARY_VERSION + 1;
+  assert!(BinaryReader::new(&future[..]).is_err());
+}
+
+#[test]
+fn binary_output_resumed() {
+  let path = std::env::temp_dir().join("sudoku_rs_binary_output_resumed.bin");
+  let filename = Some(path.to_str().unwrap().to_string());
+  let questions = questions();
+
+  let mut output = Output::new_with_format(&filename, OutputFormat::Binary).unwrap();
+  for (question, solution) in &questions[..4] {
+    output.output_board_with_solution(question, solution).unwrap();
+  }
+  let mut output = Output::resume(&filename, OutputFormat::Binary).unwrap();
+  for (question, solution) in &questions[4..] {
+    output.output_board_with_solution(question, solution).unwrap();
+  }
+
+  let records = read_records(path.to_str().unwrap()).unwrap();
+  assert_eq!(records.len(), 10);
+  for (record, (question, solution)) in records.iter().zip(&questions) {
+    assert_eq!(record.question, question.to_u8_vec());
+    assert_eq!(record.answer, Some(solution.to_u8_vec()));
+  }
+}
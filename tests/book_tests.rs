@@ -0,0 +1,34 @@
+use sudoku_rs::*;
+
+mod common;
+
+#[test]
+fn difficulty_levels() {
+  // A single slot missing only needs a naked single
+  let easy = Board4x4::from_u8_vec(&[1, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 0]);
+  assert_eq!(easy.difficulty(), Difficulty::Easy);
+  let medium = Board9x9::from_line("040003080000001046200000700009500400726000850030078629600012900000000500004807060").unwrap();
+  assert_eq!(medium.difficulty(), Difficulty::Medium);
+  let expert = Board9x9::from_line("800000000003600000070090200050007000000045700000100030001000068008500010090000400").unwrap();
+  assert_eq!(expert.difficulty(), Difficulty::Expert);
+  assert_eq!("hard".parse::<Difficulty>(), Ok(Difficulty::Hard));
+}
+
+#[test]
+fn book_pages_and_labels() {
+  let puzzles = common::questions::<Board4x4>(1, 5, 6);
+
+  // 2 pages of puzzles and 1 page of answers
+  let pdf = PuzzleBook::new("Test book").puzzles_per_page(4).answers_per_page(6).render(&puzzles);
+  let pdf = String::from_utf8_lossy(&pdf);
+  assert!(pdf.starts_with("%PDF"));
+  assert!(pdf.contains("/Count 3"));
+  assert!(pdf.contains("(Puzzle 5 - Easy)"));
+  assert!(pdf.contains("(Answer 5)"));
+  assert!(pdf.contains("(Test book - Answers)"));
+
+  let pdf = PuzzleBook::new("Test book").answers(false).render(&puzzles);
+  let pdf = String::from_utf8_lossy(&pdf);
+  assert!(pdf.contains("/Count 2"));
+  assert!(!pdf.contains("Answer"));
+}
@@ -0,0 +1,44 @@
+use sudoku_rs::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+#[test]
+fn node_budget_exhausted() {
+  let ctx = SolvingContext::deterministic().with_budget(SearchBudget::unlimited().with_max_nodes(100));
+  let mut solutions = Board9x9::default().solve_with_ctx(ctx);
+  let count = solutions.by_ref().count();
+  assert!(count > 0);
+  assert_eq!(solutions.status(), SearchStatus::BudgetExhausted(BudgetExhausted::Nodes));
+  assert_eq!(solutions.stats().nodes, 100);
+
+  // Extending the budget lets the search continue
+  solutions.ctx_mut().budget_mut().max_nodes = Some(200);
+  assert!(solutions.try_next().unwrap().is_some());
+}
+
+#[test]
+fn complete_search_status() {
+  let mut solutions = Board4x4::default().solve_with_ctx(SolvingContext::deterministic());
+  assert_eq!(solutions.by_ref().count(), 288);
+  assert_eq!(solutions.status(), SearchStatus::Complete);
+  assert_eq!(solutions.try_next(), Ok(None));
+}
+
+#[test]
+fn deadline_exhausted() {
+  let budget = SearchBudget::unlimited().with_deadline(Instant::now() - Duration::from_secs(1));
+  let mut solutions = Board9x9::default().solve_with_ctx(SolvingContext::random().with_budget(budget));
+  assert_eq!(solutions.try_next(), Err(BudgetExhausted::Deadline));
+}
+
+#[test]
+fn cancelled() {
+  let cancel = Arc::new(AtomicBool::new(false));
+  let budget = SearchBudget::unlimited().with_cancel_flag(cancel.clone());
+  let mut solutions = Board9x9::default().solve_with_ctx(SolvingContext::random().with_budget(budget));
+  assert!(solutions.next().is_some());
+  cancel.store(true, Ordering::Relaxed);
+  assert!(solutions.next().is_none());
+  assert_eq!(solutions.status(), SearchStatus::BudgetExhausted(BudgetExhausted::Cancelled));
+}
@@ -0,0 +1,65 @@
+use sudoku_rs::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+#[test]
+fn canonical_form_of_isomorphs() {
+  let mut rng = StdRng::seed_from_u64(3);
+  let board = Board9x9::default().solve_with_ctx(SolvingContext::random_with_seed(4)).next().unwrap();
+  let (canonical, transform) = board.canonical_form();
+  assert_eq!(board.transform(&transform), canonical);
+  for _ in 0..3 {
+    let (isomorph, _) = board.random_isomorph_with_rng(&mut rng);
+    assert_eq!(isomorph.canonical_form().0, canonical);
+    assert!(isomorph.is_isomorphic(&board));
+  }
+}
+
+#[test]
+fn canonical_form_of_puzzle() {
+  let mut rng = StdRng::seed_from_u64(5);
+  let mut puzzle = Board9x9::default().solve_with_ctx(SolvingContext::random_with_seed(6)).next().unwrap();
+  puzzle.put_random_unknowns_with_rng(50, &mut rng);
+  let (canonical, transform) = puzzle.canonical_form();
+  assert_eq!(puzzle.transform(&transform), canonical);
+  let (isomorph, _) = puzzle.random_isomorph_with_rng(&mut rng);
+  assert!(isomorph.is_isomorphic(&puzzle));
+}
+
+#[test]
+fn non_isomorphic_4x4() {
+  let board = sudoku4x4![
+    1, 0, 0, 0;
+    0, 0, 0, 0;
+    0, 0, 0, 0;
+    0, 0, 0, 0;
+  ];
+  let other = sudoku4x4![
+    1, 2, 0, 0;
+    0, 0, 0, 0;
+    0, 0, 0, 0;
+    0, 0, 0, 0;
+  ];
+  assert!(!board.is_isomorphic(&other));
+  assert!(board.is_isomorphic(&sudoku4x4![
+    0, 0, 0, 0;
+    0, 0, 0, 0;
+    0, 0, 0, 0;
+    0, 0, 3, 0;
+  ]));
+}
+
+#[test]
+fn records_of_flat_boards() {
+  // 16 flat 4x4 boards have the shape of a nested board of size 16
+  let boards = Board4x4::default().solve_with_ctx(SolvingContext::deterministic()).take(16).collect::<Vec<_>>();
+  let json = serde_json::to_string(&boards.iter().map(Board::to_u8_vec).collect::<Vec<_>>()).unwrap();
+  let records = utils::parse_records(&json).unwrap();
+  assert_eq!(records.len(), 16);
+  assert_eq!(records.iter().map(|record| Board4x4::from_u8_vec(&record.question)).collect::<Vec<_>>(), boards);
+
+  // Nested boards are still single records
+  let json = serde_json::to_string(&[[1, 2, 3, 4], [3, 4, 1, 2], [0, 0, 0, 0], [0, 0, 0, 0]]).unwrap();
+  let records = utils::parse_records(&json).unwrap();
+  assert_eq!(records.len(), 1);
+  assert_eq!(records[0].question, vec![1, 2, 3, 4, 3, 4, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0]);
+}
@@ -0,0 +1,57 @@
+use sudoku_rs::{*, utils::parse_entry_records};
+
+fn puzzle() -> Board4x4 {
+  Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2])
+}
+
+#[test]
+fn wrong_entries_of_unique_puzzle() {
+  let entries = Board4x4::from_u8_vec(&[1, 2, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 4, 2]);
+  let report = puzzle().check_entries(&entries).unwrap();
+  assert!(report.unique);
+  assert_eq!(report.givens, vec![(0, 0), (1, 2), (2, 1), (3, 3)]);
+  assert_eq!(report.entries, vec![(0, 1), (3, 2)]);
+  assert_eq!(report.wrong, vec![(0, 1)]);
+  assert!(!report.solvable);
+  assert!(!report.is_solved());
+}
+
+#[test]
+fn solved_puzzle() {
+  let solution = puzzle().solve().next().unwrap();
+  let report = puzzle().check_entries(&solution).unwrap();
+  assert_eq!(report.entries.len(), 12);
+  assert!(report.empty.is_empty());
+  assert!(report.is_solved());
+}
+
+#[test]
+fn wrong_entries_of_ambiguous_puzzle() {
+  // Each of the two 1s appears in some solution, but not both of them
+  let puzzle = Board4x4::default();
+  let entries = Board4x4::from_u8_vec(&[1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+  let report = puzzle.check_entries(&entries).unwrap();
+  assert!(!report.unique);
+  assert!(report.wrong.is_empty());
+  assert!(!report.solvable);
+
+  let entries = Board4x4::from_u8_vec(&[1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+  let report = puzzle.check_entries(&entries).unwrap();
+  assert!(report.wrong.is_empty());
+  assert!(report.solvable);
+}
+
+#[test]
+fn changed_given() {
+  let mut entries = puzzle();
+  entries.set(&(0, 0), Board4x4Element::U2);
+  assert!(puzzle().check_entries(&entries).is_err());
+}
+
+#[test]
+fn parse_entries() {
+  let records = parse_entry_records(r#"{"q": [1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2], "entries": [[1, 2, 0, 0], [0, 0, 3, 0], [0, 4, 0, 0], [0, 0, 0, 2]]}"#).unwrap();
+  assert_eq!(records.len(), 1);
+  assert_eq!(records[0].entries[1], 2);
+  assert!(parse_entry_records(r#"{"puzzle": [1, 0, 0, 0], "entries": [1, 2]}"#).is_err());
+}
@@ -0,0 +1,41 @@
+use sudoku_rs::*;
+
+#[test]
+fn resume_deterministic_search() {
+  let all = Board4x4::default().solve_with_ctx(SolvingContext::deterministic()).collect::<Vec<_>>();
+  let mut solutions = Board4x4::default().solve_with_ctx(SolvingContext::deterministic());
+  let first = solutions.by_ref().take(100).collect::<Vec<_>>();
+  let checkpoint = solutions.checkpoint();
+  let resumed = BoardSolutions::<Board4x4>::from_checkpoint(&checkpoint, SolvingContext::deterministic()).unwrap();
+  let rest = resumed.collect::<Vec<_>>();
+  assert_eq!([first, rest].concat(), all);
+}
+
+#[test]
+fn resume_random_search() {
+  let all = Board9x9::default().solve_with_ctx(SolvingContext::random_with_seed(3)).take(20).collect::<Vec<_>>();
+  let mut solutions = Board9x9::default().solve_with_ctx(SolvingContext::random_with_seed(3));
+  let first = solutions.by_ref().take(10).collect::<Vec<_>>();
+
+  // Go through a string, as a checkpoint file would
+  let checkpoint = serde_json::from_str(&solutions.checkpoint().to_string()).unwrap();
+  let resumed = BoardSolutions::<Board9x9>::from_checkpoint(&checkpoint, SolvingContext::random()).unwrap();
+  let rest = resumed.take(10).collect::<Vec<_>>();
+  assert_eq!([first, rest].concat(), all);
+}
+
+#[test]
+fn resume_keeps_stats() {
+  let mut solutions = Board4x4::default().solve_with_ctx(SolvingContext::deterministic());
+  solutions.by_ref().take(10).for_each(drop);
+  let nodes = solutions.stats().nodes;
+  let resumed = BoardSolutions::<Board4x4>::from_checkpoint(&solutions.checkpoint(), SolvingContext::deterministic()).unwrap();
+  assert_eq!(resumed.stats().nodes, nodes);
+  assert_eq!(resumed.stats().solutions, 10);
+}
+
+#[test]
+fn invalid_checkpoint() {
+  let checkpoint = serde_json::json!({ "stack": [[1, 2, 3]] });
+  assert!(BoardSolutions::<Board4x4>::from_checkpoint(&checkpoint, SolvingContext::deterministic()).is_err());
+}
@@ -0,0 +1,70 @@
+use sudoku_rs::*;
+
+/// The model where exactly the digits of `board` are true
+fn model_of<B: Board>(board: &B) -> Vec<i32> {
+  let size = B::size();
+  B::slots().flat_map(|slot| {
+    let digit : u8 = (*board.get(&slot)).into();
+    (1..=size as u8).map(move |d| if d == digit { Cnf::variable::<B>(&slot, d) } else { -Cnf::variable::<B>(&slot, d) })
+  }).collect()
+}
+
+fn satisfies(cnf: &Cnf, model: &[i32]) -> bool {
+  cnf.clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit)))
+}
+
+#[test]
+fn solution_satisfies_encoding() {
+  let board = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 4, 0, 0]);
+  let cnf = board.to_cnf();
+  assert_eq!(cnf.num_variables, 64);
+  let solutions = board.solve_with_ctx(SolvingContext::deterministic()).collect::<Vec<_>>();
+  for solution in &solutions {
+    assert!(satisfies(&cnf, &model_of(solution)));
+  }
+
+  // Every complete grid that is not a solution violates the encoding
+  let others = Board4x4::default().solve_with_ctx(SolvingContext::deterministic()).filter(|grid| !solutions.contains(grid));
+  for grid in others {
+    assert!(!satisfies(&cnf, &model_of(&grid)));
+  }
+}
+
+#[test]
+fn diagonal_encoding() {
+  let cnf = Board9x9::default().to_cnf_with_options(&CnfOptions { diagonals: true });
+  let plain = Board9x9::default().to_cnf();
+  assert_eq!(cnf.clauses.len(), plain.clauses.len() + 2 * 9 * (1 + 36));
+  let solution = Board9x9::default().solve().next().unwrap();
+  assert!(satisfies(&plain, &model_of(&solution)));
+}
+
+#[test]
+fn candidates_encoding() {
+  // Only the grids with 1 or 2 in the first slot are left
+  let mut candidates = vec![vec![1, 2, 3, 4]; 16];
+  candidates[0] = vec![1, 2];
+  let mut cnf = Board4x4::default().to_cnf();
+  cnf.add_candidates::<Board4x4>(&candidates);
+  for grid in Board4x4::default().solve() {
+    let first : u8 = (*grid.get(&(0, 0))).into();
+    assert_eq!(satisfies(&cnf, &model_of(&grid)), first <= 2);
+  }
+}
+
+#[test]
+fn decode_model() {
+  let solution = Board9x9::default().solve().next().unwrap();
+  assert_eq!(Board9x9::from_model(&model_of(&solution)), Ok(solution));
+  assert!(Board9x9::from_model(&[1, 2]).is_err());
+  assert!(Board9x9::from_model(&[]).is_err());
+}
+
+#[test]
+fn parse_solver_output() {
+  assert_eq!(parse_model("c comment\ns SATISFIABLE\nv 1 -2 3\nv -4 0\n"), Ok(vec![1, -2, 3, -4]));
+  assert_eq!(parse_model("SAT\n1 -2 3 0\n"), Ok(vec![1, -2, 3]));
+  assert!(parse_model("s UNSATISFIABLE\n").is_err());
+  assert!(parse_model("UNSAT\n").is_err());
+  assert!(parse_model("v 1 x 0\n").is_err());
+}
@@ -0,0 +1,11 @@
+use sudoku_rs::*;
+
+/// Random questions along with their solution, the same ones for a given seed
+pub fn questions<B: Board>(seed: u64, count: usize, num_unknowns: usize) -> Vec<(B, B)> {
+  let mut rng = SolverRng::seed_from_u64(seed);
+  B::default().solve_with_ctx(SolvingContext::random_with_seed(seed)).take(count).map(|solution| {
+    let mut question = solution.clone();
+    question.put_random_unknowns_with_rng(num_unknowns, &mut rng);
+    (question, solution)
+  }).collect()
+}
@@ -0,0 +1,33 @@
+use sudoku_rs::*;
+
+#[test]
+fn element_to_string() {
+  assert_eq!(Board4x4Element::from(3).to_string(), "3");
+  assert_eq!(Board9x9Element::from(0).to_string(), "0");
+  assert_eq!(format!("{}", Board9x9Element::from(9)), "9");
+}
+
+#[test]
+fn from_u8_vec_with_vec_ref() {
+  let v : Vec<u8> = vec![1, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 1];
+  let board = Board4x4::from_u8_vec(&v);
+  assert_eq!(board.to_u8_vec(), v);
+  assert_eq!(Board4x4::from_u8_vec(&v[..]), board);
+}
+
+#[test]
+#[should_panic(expected = "Invalid number of elements")]
+fn from_u8_vec_rejects_short_input() {
+  Board4x4::from_u8_vec(&[1, 2, 3, 4]);
+}
+
+#[test]
+fn solving_context_constructors() {
+  use rand::Rng;
+  let mut ctx = SolvingContext::random_with_seed(3);
+  assert!(ctx.is_random());
+  let n : u8 = ctx.rng().unwrap().gen_range(0, 10);
+  assert!(n < 10);
+  let mut ctx = SolvingContext::deterministic();
+  assert!(!ctx.is_random() && ctx.rng().is_none());
+}
@@ -0,0 +1,64 @@
+use sudoku_rs::{*, utils::*};
+
+mod common;
+
+fn questions() -> Vec<(Board4x4, Board4x4)> {
+  common::questions(7, 4, 6)
+}
+
+#[test]
+fn board_line_codec() {
+  let line = "1...3.......4..2";
+  let board = Board4x4::from_line(line).unwrap();
+  assert_eq!(board.to_line(), line);
+  assert_eq!(Board4x4::from_line("1000300000004002"), Ok(board));
+  assert!(Board4x4::from_line("1...3.......4..").is_err());
+  assert!(Board4x4::from_line("1...3.......4..5").is_err());
+  assert!(Board9x9::from_line(line).is_err());
+  assert!(digits_from_line("1...3...x...4..2").is_err());
+}
+
+#[test]
+fn csv_output_and_input() {
+  let path = std::env::temp_dir().join("sudoku_rs_csv_output_and_input.csv");
+  let filename = Some(path.to_str().unwrap().to_string());
+  let questions = questions();
+  let mut output = Output::new_with_format(&filename, OutputFormat::Csv).unwrap();
+  output.output_init().unwrap();
+  for (question, solution) in &questions[..2] {
+    output.output_board_with_solution(question, solution).unwrap();
+  }
+  let mut output = Output::resume(&filename, OutputFormat::Csv).unwrap().with_csv_difficulty(true);
+  output.output_init().unwrap();
+  for (question, solution) in &questions[2..] {
+    output.output_board_with_solution(question, solution).unwrap();
+  }
+
+  let contents = std::fs::read_to_string(&path).unwrap();
+  let lines = contents.lines().collect::<Vec<_>>();
+  assert_eq!(lines.len(), 5);
+  assert_eq!(lines[0], CSV_HEADER);
+  let (question, solution) = &questions[3];
+  let row = format!("4,{},{},10,{}", question.to_line(), solution.to_line(), question.difficulty());
+  assert_eq!(lines[4], row);
+  // The difficulty is only rated on demand
+  let (question, solution) = &questions[0];
+  assert_eq!(lines[1], format!("1,{},{},10,", question.to_line(), solution.to_line()));
+
+  let records = read_records(path.to_str().unwrap()).unwrap();
+  for (record, (question, solution)) in records.iter().zip(&questions) {
+    assert_eq!(record.question, question.to_u8_vec());
+    assert_eq!(record.answer, Some(solution.to_u8_vec()));
+  }
+}
+
+#[test]
+fn csv_from_spreadsheets() {
+  // Columns in another order, quoted fields, a byte order mark, CRLF line
+  // endings, and a record without answer
+  let contents = "\u{feff}\"question\",\"id\",\"answer\"\r\n\"1...3.......4..2\",\"a\",\"\"\r\n";
+  let records = parse_records(contents).unwrap();
+  assert_eq!(records, vec![InputRecord { question: vec![1, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 2], answer: None, candidates: None }]);
+  assert!(parse_records("id,question\n1,1...3...\n").is_err());
+  assert!(parse_records("id,question\n1\n").is_err());
+}
@@ -0,0 +1,68 @@
+use sudoku_rs::*;
+
+#[test]
+fn conflicting_givens() {
+  let board = sudoku4x4![
+    1, 0, 0, 1;
+    0, 0, 0, 0;
+    0, 0, 0, 0;
+    0, 0, 0, 0;
+  ];
+  assert_eq!(board.validate(), Err(vec![Conflict { digit: 1, unit: Unit::Row(0), slots: ((0, 0), (0, 3)) }]));
+  assert!(board.solve().next().is_none());
+  assert_eq!(board.explain_unsat(), Some(UnsatExplanation::Conflicts(board.conflicts())));
+}
+
+#[test]
+fn solvable_board() {
+  let board = Board9x9::from_u8_vec(&[
+    5, 3, 0, 0, 7, 0, 0, 0, 0,
+    6, 0, 0, 1, 9, 5, 0, 0, 0,
+    0, 9, 8, 0, 0, 0, 0, 6, 0,
+    8, 0, 0, 0, 6, 0, 0, 0, 3,
+    4, 0, 0, 8, 0, 3, 0, 0, 1,
+    7, 0, 0, 0, 2, 0, 0, 0, 6,
+    0, 6, 0, 0, 0, 0, 2, 8, 0,
+    0, 0, 0, 4, 1, 9, 0, 0, 5,
+    0, 0, 0, 0, 8, 0, 0, 7, 9,
+  ]);
+  assert_eq!(board.validate(), Ok(()));
+  assert_eq!(board.explain_unsat(), None);
+}
+
+#[test]
+fn minimal_core() {
+  // The top right slot has no possible answer, the other givens are unrelated
+  let board = Board9x9::from_u8_vec(&[
+    1, 2, 3, 4, 5, 6, 7, 8, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 9,
+    0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 1, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 2, 0,
+  ]);
+  assert_eq!(board.validate(), Ok(()));
+  let core = match board.explain_unsat() {
+    Some(UnsatExplanation::Core(core)) => core,
+    other => panic!("Expected a core, got {:?}", other),
+  };
+  assert_eq!(core.len(), 9);
+  assert!(core.contains(&((5, 8), 9)));
+  assert!(!core.contains(&((7, 4), 1)));
+
+  // Removing any given of the core makes it solvable
+  let core_board = Board9x9::from_u8_vec(&{
+    let mut v = vec![0; 81];
+    for ((i, j), digit) in &core { v[i * 9 + j] = *digit; }
+    v
+  });
+  assert!(core_board.solve().next().is_none());
+  for ((i, j), _) in &core {
+    let mut smaller = core_board.clone();
+    smaller.set(&(*i, *j), Board9x9Element::default());
+    assert!(smaller.solve().next().is_some());
+  }
+}
@@ -0,0 +1,95 @@
+use rand::{SeedableRng, rngs::StdRng};
+use sudoku_rs::{*, utils::{Output, read_records}};
+
+fn game() -> Game<Board4x4> {
+  Game::new(Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2])).unwrap()
+}
+
+#[test]
+fn enter_and_undo() {
+  let mut game = game();
+  assert!(!game.enter(2), "The top left slot holds a given");
+  game.move_cursor(0, -1);
+  assert_eq!(game.cursor(), (0, 3));
+  assert!(game.enter(4));
+  assert!(game.enter(3));
+  assert!(game.clear());
+  assert_eq!(game.get(&(0, 3)), 0);
+  assert!(game.undo());
+  assert_eq!(game.get(&(0, 3)), 3);
+  assert!(game.undo());
+  assert_eq!(game.get(&(0, 3)), 4);
+  assert!(game.redo());
+  assert_eq!(game.get(&(0, 3)), 3);
+  assert!(game.enter(2));
+  assert!(!game.redo(), "A new change drops the undone ones");
+}
+
+#[test]
+fn pencil_marks() {
+  let mut game = game();
+  game.move_cursor(0, 1);
+  assert!(game.toggle_mark(2));
+  assert!(game.toggle_mark(3));
+  assert!(game.toggle_mark(2));
+  assert_eq!(game.marks(&(0, 1)), vec![3]);
+  game.enter(2);
+  assert!(game.marks(&(0, 1)).is_empty());
+  assert!(!game.toggle_mark(1));
+}
+
+#[test]
+fn conflicts_and_hints() {
+  let mut game = game();
+  game.move_cursor(0, 1);
+  game.enter(1);
+  assert_eq!(game.conflicting_slots(), vec![(0, 0), (0, 1)]);
+  assert_eq!(game.hint(), Some((0, 1)));
+  assert!(game.conflicting_slots().is_empty());
+  while game.hint().is_some() {}
+  assert!(game.is_solved());
+}
+
+#[test]
+fn unsolvable_puzzle() {
+  assert!(Game::new(Board4x4::from_u8_vec(&[1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])).is_err());
+}
+
+#[test]
+fn ambiguous_puzzle() {
+  let err = Game::new(Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])).err();
+  assert_eq!(err, Some("The puzzle has more than one solution".to_string()));
+}
+
+#[test]
+fn stored_solution() {
+  // A generated record whose question has several solutions
+  let path = std::env::temp_dir().join("sudoku_rs_game_stored_solution.json");
+  let filename = Some(path.to_str().unwrap().to_string());
+  let question = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+  let solution = question.solve().nth(5).unwrap();
+  let mut output = Output::new(&filename).unwrap();
+  output.output_init().unwrap();
+  output.output_board_with_solution(&question, &solution).unwrap();
+  output.output_finish().unwrap();
+
+  let records = read_records(filename.as_ref().unwrap()).unwrap();
+  let (question, answer) = (&records[0].question, records[0].answer.as_ref().unwrap());
+  let mut game = Game::with_solution(Board4x4::from_u8_vec(question), Board4x4::from_u8_vec(answer)).unwrap();
+  while game.hint().is_some() {}
+  assert!(game.is_solved());
+  assert_eq!(game.board(), &solution);
+
+  // The solution has to agree with the givens
+  let wrong = Board4x4::from_u8_vec(&[2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+  assert_eq!(Game::with_solution(wrong, solution.clone()).err(), Some("The solution does not agree with the puzzle".to_string()));
+  assert!(Game::with_solution(Board4x4::default(), Board4x4::default()).is_err());
+}
+
+#[test]
+fn generated_puzzle_is_unique() {
+  let mut rng = StdRng::seed_from_u64(3);
+  let puzzle : Board9x9 = generate_puzzle(40, &mut rng);
+  assert_eq!(puzzle.unknown_slots().len(), 40);
+  assert_eq!(puzzle.solve().take(2).count(), 1);
+}
@@ -0,0 +1,79 @@
+use sudoku_rs::*;
+
+const SLOT_SELECTIONS : [SlotSelection; 4] = [
+  SlotSelection::FirstEmpty,
+  SlotSelection::MinimumRemainingValues,
+  SlotSelection::MinimumRemainingValuesDegree,
+  SlotSelection::MostConstrainedUnit,
+];
+
+const VALUE_ORDERINGS : [ValueOrdering; 3] = [
+  ValueOrdering::Natural,
+  ValueOrdering::Random,
+  ValueOrdering::LeastConstrainingValue,
+];
+
+#[test]
+fn all_strategies_enumerate_4x4() {
+  for slot_selection in SLOT_SELECTIONS {
+    for value_ordering in VALUE_ORDERINGS {
+      let ctx = SolvingContext::random_with_seed(7).with_heuristic(Strategy::new(slot_selection, value_ordering));
+      let mut solutions = Board4x4::default().solve_with_ctx(ctx).collect::<Vec<_>>();
+      solutions.sort();
+      solutions.dedup();
+      assert_eq!(solutions.len(), 288, "{:?} {:?}", slot_selection, value_ordering);
+    }
+  }
+}
+
+#[test]
+fn all_strategies_solve_9x9() {
+  let board = sudoku9x9![
+    9, 0, 0, 0, 0, 2, 0, 0, 6;
+    0, 8, 0, 0, 0, 7, 0, 5, 0;
+    0, 0, 7, 0, 0, 8, 1, 0, 0;
+    0, 0, 0, 6, 0, 0, 7, 1, 2;
+    0, 0, 0, 0, 5, 0, 0, 0, 0;
+    1, 2, 3, 0, 0, 4, 0, 0, 0;
+    0, 0, 4, 8, 0, 0, 3, 0, 0;
+    0, 3, 0, 7, 0, 0, 0, 2, 0;
+    5, 0, 0, 4, 0, 0, 0, 0, 1;
+  ];
+  let expected = board.solve_with_ctx(SolvingContext::deterministic()).collect::<Vec<_>>();
+  assert_eq!(expected.len(), 1);
+  for slot_selection in SLOT_SELECTIONS {
+    for value_ordering in VALUE_ORDERINGS {
+      let ctx = SolvingContext::deterministic().with_heuristic(Strategy::new(slot_selection, value_ordering));
+      assert_eq!(board.solve_with_ctx(ctx).collect::<Vec<_>>(), expected);
+    }
+  }
+}
+
+#[test]
+fn natural_order_is_increasing() {
+  let ctx = SolvingContext::deterministic().with_heuristic(Strategy::new(SlotSelection::FirstEmpty, ValueOrdering::Natural));
+  let solutions = Board4x4::default().solve_with_ctx(ctx).collect::<Vec<_>>();
+  let mut sorted = solutions.clone();
+  sorted.sort();
+  assert_eq!(solutions, sorted);
+}
+
+/// Branch on the last unknown slot, trying the largest answer first
+struct LastSlot;
+
+impl Heuristic for LastSlot {
+  fn select_slot(&self, node: &dyn SearchNode, _: Option<&mut SolverRng>) -> Option<Slot> {
+    node.unknown_slots().pop()
+  }
+
+  fn order_values(&self, _: &dyn SearchNode, _: &Slot, values: &mut Vec<u8>, _: Option<&mut SolverRng>) {
+    values.sort_unstable_by(|a, b| b.cmp(a));
+  }
+}
+
+#[test]
+fn custom_heuristic() {
+  let solutions = Board4x4::default().solve_with_ctx(SolvingContext::deterministic().with_heuristic(LastSlot)).collect::<Vec<_>>();
+  assert_eq!(solutions.len(), 288);
+  assert_eq!(solutions[0].to_u8_vec(), vec![4, 3, 2, 1, 2, 1, 4, 3, 3, 4, 1, 2, 1, 2, 3, 4]);
+}
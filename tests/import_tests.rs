@@ -0,0 +1,70 @@
+use sudoku_rs::{*, utils::*};
+
+const PUZZLE : &str = "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+
+#[test]
+fn import_sdk() {
+  let contents = "#A Someone\n#D A puzzle\n#L Easy\n..3.2.6..\n9..3.5..1\n..18.64..\n..81.29..\n7.......8\n..67.82..\n..26.95..\n8..2.3..9\n..5.1.3..\n";
+  let puzzle = parse_puzzle(contents).unwrap();
+  assert_eq!(puzzle.format, PuzzleFormat::Sdk);
+  assert_eq!(puzzle.board.to_line(), PUZZLE);
+  assert_eq!(puzzle.candidates, None);
+
+  // Files with sections, the player's progress coming after the puzzle
+  let grid = PUZZLE.as_bytes().chunks(9).map(|row| format!("{}\n", String::from_utf8_lossy(row))).collect::<String>();
+  let contents = format!("[Puzzle]\n{}[State]\n{}", grid, grid.replace('.', "4"));
+  assert_eq!(parse_puzzle(&contents).unwrap().board.to_line(), PUZZLE);
+
+  // Puzzle files are read as single records
+  assert_eq!(parse_records(&contents).unwrap()[0].question, Board9x9::from_line(PUZZLE).unwrap().to_u8_vec());
+}
+
+#[test]
+fn import_simple_sudoku() {
+  let contents = "\
+    ..3|.2.|6..\n9..|3.5|..1\n..1|8.6|4..\n-----------\n\
+    ..8|1.2|9..\n7..|...|..8\n..6|7.8|2..\n-----------\n\
+    ..2|6.9|5..\n8..|2.3|..9\n..5|.1.|3..\n";
+  assert_eq!(detect_puzzle_format(contents), Some(PuzzleFormat::SimpleSudoku));
+  assert_eq!(parse_puzzle(contents).unwrap().board.to_line(), PUZZLE);
+  assert_eq!(detect_puzzle_format("[[1, 2], [3, 4]]"), None);
+  assert_eq!(detect_puzzle_format(&format!("{}\n", PUZZLE)), None);
+}
+
+#[test]
+fn import_hodoku_pencil_marks() {
+  let contents = "
+.-------------------.------------------.-------------------.
+| 45    4578    3  | 49   2      147 | 6   5789   57    |
+| 9     24678   47 | 3    47     5   | 78  278    1     |
+| 25    257     1  | 8    79     6   | 4   23579  2357  |
+:-------------------+------------------+-------------------:
+| 345   345     8  | 1    3456   2   | 9   34567  34567 |
+| 7     123459  49 | 459  34569  4   | 1   13456  8     |
+| 1345  13459   6  | 7    3459   8   | 2   1345   345   |
+:-------------------+------------------+-------------------:
+| 134   1347    2  | 6    478    9   | 5   1478   47    |
+| 8     1467    47 | 2    457    3   | 17  1467   9     |
+| 46    4679    5  | 4    1      47  | 3   24678  2467  |
+'-------------------'------------------'-------------------'
+";
+  let puzzle = parse_puzzle(contents).unwrap();
+  assert_eq!(puzzle.format, PuzzleFormat::HoDoKu);
+  let candidates = puzzle.candidates.as_ref().unwrap();
+  assert_eq!(candidates[0], vec![4, 5]);
+  assert_eq!(candidates[80], vec![2, 4, 6, 7]);
+
+  // Slots with a single candidate are solved
+  assert_eq!(*puzzle.board.get(&(4, 5)), 4.into());
+  let solution = puzzle.board.solve().next().unwrap();
+  assert!(puzzle.allows(&solution));
+  assert_eq!(solution, Board9x9::from_line(PUZZLE).unwrap().solve().next().unwrap());
+
+  // Records keep the candidates
+  let record = &parse_records(contents).unwrap()[0];
+  assert_eq!(record.candidates, puzzle.candidates);
+  assert!(record.allows(&solution.to_u8_vec()));
+  let mut other = solution.to_u8_vec();
+  other[0] = 6;
+  assert!(!record.allows(&other));
+}
@@ -71,3 +71,26 @@ fn test_4x4_diagonal_slots_2() {
   assert_eq!(v[2], (2, 1));
   assert_eq!(v[3], (3, 0));
 }
+
+#[test]
+fn test_4x4_solutions_distinct() {
+  let solutions : std::collections::HashSet<_> = Board4x4::default().solve().collect();
+  assert_eq!(solutions.len(), 288);
+  let mut sorted : Vec<_> = solutions.into_iter().collect();
+  sorted.sort();
+  assert!(sorted.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_element_set_complement_equality() {
+  let mut set = Board9x9ElementSet::default();
+  set.insert(&Board9x9Element::from(3));
+  let mut others = Board9x9ElementSet::default();
+  for d in (1..=9).filter(|d| *d != 3) {
+    others.insert(&Board9x9Element::from(d));
+  }
+  assert!(set.complement() == others);
+  assert!(set.complement().complement() == set);
+  assert_eq!(Board4x4ElementSet::default().complement().count(), 4);
+  assert!(Board4x4ElementSet::default().complement().complement() == Board4x4ElementSet::default());
+}
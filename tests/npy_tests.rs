@@ -0,0 +1,83 @@
+use sudoku_rs::{*, utils::*};
+
+mod common;
+
+fn questions() -> Vec<(Board9x9, Board9x9)> {
+  common::questions(5, 6, 45)
+}
+
+/// The type, the shape, and the data of the array in a `.npy` file
+fn read_npy(filename: &str) -> (String, Vec<usize>, Vec<u8>) {
+  let bytes = std::fs::read(filename).unwrap();
+  assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+  let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+  assert_eq!((10 + header_len) % 64, 0);
+  let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+  let descr = header.split('\'').nth(3).unwrap().to_string();
+  let shape = header.split("'shape': (").nth(1).unwrap().split(')').next().unwrap();
+  let shape = shape.split(',').filter_map(|n| n.trim().parse().ok()).collect();
+  (descr, shape, bytes[10 + header_len..].to_vec())
+}
+
+fn npy_path(name: &str) -> String {
+  std::env::temp_dir().join(name).to_str().unwrap().to_string()
+}
+
+#[test]
+fn npy_questions_answers_and_mask() {
+  let filename = Some(npy_path("sudoku_rs_npy_questions.npy"));
+  let questions = questions();
+  let mut output = Output::new_with_format(&filename, OutputFormat::Npy).unwrap();
+  for (question, solution) in &questions {
+    output.output_board_with_solution(question, solution).unwrap();
+  }
+  output.output_finish().unwrap();
+
+  let (descr, shape, data) = read_npy(&npy_path("sudoku_rs_npy_questions.questions.npy"));
+  assert_eq!((descr.as_str(), shape), ("|u1", vec![6, 9, 9]));
+  assert_eq!(data, questions.iter().flat_map(|(question, _)| question.to_u8_vec()).collect::<Vec<_>>());
+  let (descr, shape, data) = read_npy(&npy_path("sudoku_rs_npy_questions.answers.npy"));
+  assert_eq!((descr.as_str(), shape), ("|u1", vec![6, 9, 9]));
+  assert_eq!(data, questions.iter().flat_map(|(_, solution)| solution.to_u8_vec()).collect::<Vec<_>>());
+  let (descr, shape, data) = read_npy(&npy_path("sudoku_rs_npy_questions.mask.npy"));
+  assert_eq!((descr.as_str(), shape), ("|b1", vec![6, 9, 9]));
+  assert_eq!(data.iter().filter(|given| **given == 1).count(), 6 * (81 - 45));
+}
+
+#[test]
+fn npy_one_hot() {
+  let filename = Some(npy_path("sudoku_rs_npy_one_hot.npy"));
+  let (question, _) = &questions()[0];
+  let mut output = Output::new_with_format(&filename, OutputFormat::Npy).unwrap().with_npy_one_hot(true);
+  output.output_board(question).unwrap();
+  output.output_finish().unwrap();
+
+  let (descr, shape, data) = read_npy(&npy_path("sudoku_rs_npy_one_hot.boards.npy"));
+  assert_eq!((descr.as_str(), shape), ("|u1", vec![1, 9, 9, 10]));
+  for (digit, encoded) in question.to_u8_vec().iter().zip(data.chunks(10)) {
+    assert_eq!(encoded.iter().sum::<u8>(), 1);
+    assert_eq!(encoded[*digit as usize], 1);
+  }
+}
+
+#[test]
+fn npy_output_resumed() {
+  let filename = Some(npy_path("sudoku_rs_npy_resumed.npy"));
+  let questions = questions();
+  let mut output = Output::new_with_format(&filename, OutputFormat::Npy).unwrap();
+  for (question, solution) in &questions[..2] {
+    output.output_board_with_solution(question, solution).unwrap();
+  }
+  // Interrupted without finishing: the headers still count no record
+  drop(output);
+
+  let mut output = Output::resume(&filename, OutputFormat::Npy).unwrap();
+  for (question, solution) in &questions[2..] {
+    output.output_board_with_solution(question, solution).unwrap();
+  }
+  output.output_finish().unwrap();
+
+  let (descr, shape, data) = read_npy(&npy_path("sudoku_rs_npy_resumed.answers.npy"));
+  assert_eq!((descr.as_str(), shape), ("|u1", vec![6, 9, 9]));
+  assert_eq!(data, questions.iter().flat_map(|(_, solution)| solution.to_u8_vec()).collect::<Vec<_>>());
+}
@@ -0,0 +1,55 @@
+use sudoku_rs::{*, utils::{Output, OutputFormat}};
+
+#[test]
+fn output_ndjson_lines() {
+  let path = std::env::temp_dir().join("sudoku_rs_output_ndjson_lines.jsonl");
+  let filename = Some(path.to_str().unwrap().to_string());
+  let mut output = Output::new_with_format(&filename, OutputFormat::NdJson).unwrap();
+  output.output_init().unwrap();
+  for (i, solution) in Board4x4::default().solve().take(3).enumerate() {
+    if i > 0 { output.output_separator().unwrap(); }
+    let mut question = solution.clone();
+    question.put_random_unknowns(8);
+    output.output_board_with_solution(&question, &solution).unwrap();
+  }
+  output.output_finish().unwrap();
+
+  // Every line has to be a self-contained json record
+  let contents = std::fs::read_to_string(&path).unwrap();
+  let lines = contents.lines().collect::<Vec<_>>();
+  assert_eq!(lines.len(), 3);
+  for line in lines {
+    let record : serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(record["q"].as_array().unwrap().len(), 4);
+    assert_eq!(record["a"].as_array().unwrap().len(), 4);
+  }
+}
+
+#[test]
+fn output_latex_document_resumed() {
+  let path = std::env::temp_dir().join("sudoku_rs_output_latex_document.tex");
+  let filename = Some(path.to_str().unwrap().to_string());
+  let board = Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]);
+  let solution = board.solve().next().unwrap();
+
+  let mut output = Output::new_with_format(&filename, OutputFormat::Latex).unwrap().with_latex_document(true);
+  output.output_init().unwrap();
+  output.output_board_with_solution(&board, &solution).unwrap();
+  output.output_finish().unwrap();
+
+  // Resuming appends the new pictures inside the same document
+  let mut output = Output::resume(&filename, OutputFormat::Latex).unwrap().with_latex_document(true);
+  output.output_init().unwrap();
+  output.output_board(&board).unwrap();
+  output.output_finish().unwrap();
+
+  let contents = std::fs::read_to_string(&path).unwrap();
+  assert!(contents.starts_with("\\documentclass{article}"));
+  assert!(contents.ends_with("\\end{center}\n\\end{document}\n"));
+  assert_eq!(contents.matches("\\begin{document}").count(), 1);
+  assert_eq!(contents.matches("\\end{document}").count(), 1);
+  assert_eq!(contents.matches("\\begin{tikzpicture}").count(), 3);
+  // Givens in bold, solved slots in gray
+  assert_eq!(contents.matches("\\textbf{").count(), 4 * 3);
+  assert_eq!(contents.matches("\\node[gray]").count(), 12);
+}
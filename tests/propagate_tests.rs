@@ -0,0 +1,75 @@
+use sudoku_rs::*;
+
+#[test]
+fn hidden_single() {
+  let mut board = sudoku9x9![
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 1, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 1, 0, 0;
+    0, 1, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 1, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+  ];
+  assert_eq!(board.put_determined_answers(), FillResult::Unmodified);
+  assert_eq!(board.put_hidden_singles(), FillResult::Modified);
+  assert_eq!(*board.get(&(0, 0)), Board9x9Element::U1);
+}
+
+#[test]
+fn unit_contradiction() {
+  // There is no place left for a 1 in the first row
+  let mut board = sudoku9x9![
+    0, 0, 0, 0, 0, 0, 2, 3, 4;
+    0, 1, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 1, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+    0, 0, 0, 0, 0, 0, 0, 0, 0;
+  ];
+  assert_ne!(board.clone().put_determined_answers(), FillResult::Unsatisfied);
+  assert_eq!(board.propagate(), FillResult::Unsatisfied);
+  assert!(board.solve_with_ctx(SolvingContext::deterministic()).next().is_none());
+}
+
+#[test]
+fn propagate_solves_without_guessing() {
+  let mut board = Board9x9::from_u8_vec(&[
+    0, 0, 0, 2, 6, 0, 7, 0, 1,
+    6, 8, 0, 0, 7, 0, 0, 9, 0,
+    1, 9, 0, 0, 0, 4, 5, 0, 0,
+    8, 2, 0, 1, 0, 0, 0, 4, 0,
+    0, 0, 4, 6, 0, 2, 9, 0, 0,
+    0, 5, 0, 0, 0, 3, 0, 2, 8,
+    0, 0, 9, 3, 0, 0, 0, 7, 4,
+    0, 4, 0, 0, 5, 0, 0, 3, 6,
+    7, 0, 3, 0, 1, 8, 0, 0, 0,
+  ]);
+  assert_eq!(board.propagate(), FillResult::Modified);
+  assert!(!board.has_unknown_slot());
+}
+
+#[test]
+fn hard_puzzle_branches_less() {
+  let board = Board9x9::from_u8_vec(&[
+    8, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 3, 6, 0, 0, 0, 0, 0,
+    0, 7, 0, 0, 9, 0, 2, 0, 0,
+    0, 5, 0, 0, 0, 7, 0, 0, 0,
+    0, 0, 0, 0, 4, 5, 7, 0, 0,
+    0, 0, 0, 1, 0, 0, 0, 3, 0,
+    0, 0, 1, 0, 0, 0, 0, 6, 8,
+    0, 0, 8, 5, 0, 0, 0, 1, 0,
+    0, 9, 0, 0, 0, 0, 4, 0, 0,
+  ]);
+  let mut solutions = board.solve_with_ctx(SolvingContext::deterministic());
+  let solution = solutions.next().unwrap();
+  assert!(solutions.next().is_none());
+  assert_eq!(solution.to_u8_vec()[..9], [8, 1, 2, 7, 5, 3, 6, 4, 9]);
+  assert!(solutions.stats().nodes < 1000);
+}
@@ -0,0 +1,50 @@
+use sudoku_rs::*;
+
+fn puzzle() -> Board4x4 {
+  Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2])
+}
+
+#[test]
+fn render_unicode_grid() {
+  let rendered = Renderer::unicode().blank(' ').render(&puzzle());
+  assert_eq!(rendered, "\
+┌──────┬──────┐
+│ 1    │      │
+│      │ 3    │
+├──────┼──────┤
+│    4 │      │
+│      │    2 │
+└──────┴──────┘
+");
+}
+
+#[test]
+fn render_candidates() {
+  let rendered = Renderer::ascii().candidates(true).render(&puzzle());
+  let lines = rendered.lines().collect::<Vec<_>>();
+  // Two lines of pencil marks per slot, a blank line between the two slots of
+  // a block, and the borders
+  assert_eq!(lines.len(), 4 * 2 + 2 + 3);
+  assert_eq!(lines[0], "+----------+----------+");
+  // Slot (0, 1) can be 2 or 3, slot (0, 2) can be 2 or 4
+  assert_eq!(lines[1], "|        2 |   2      |");
+  assert_eq!(lines[2], "| [1]  3   |   4    4 |");
+}
+
+#[test]
+fn render_solution_colors() {
+  let solution = puzzle().solve_with_ctx(SolvingContext::deterministic()).next().unwrap();
+  let rendered = Renderer::ascii().colors(true).render_solution(&puzzle(), &solution);
+  assert!(rendered.contains("\x1b[1m1\x1b[0m"));
+  assert_eq!(rendered.matches("\x1b[1m").count(), 4);
+  assert_eq!(rendered.matches("\x1b[36m").count(), 12);
+
+  // Without colors, the solution is drawn like any other board
+  assert_eq!(Renderer::ascii().render_solution(&puzzle(), &solution), Renderer::ascii().render(&solution));
+}
+
+#[test]
+fn renderer_from_flags() {
+  assert_eq!(Renderer::from_flags(true, false, false), Renderer::ascii());
+  assert_eq!(Renderer::from_flags(false, true, true), Renderer::unicode().candidates(true).colors(true));
+}
@@ -0,0 +1,26 @@
+use sudoku_rs::*;
+use std::collections::HashMap;
+
+#[test]
+fn uniform_samples_are_full_grids() {
+  for grid in UniformSampler::<Board9x9>::with_seed(1).burn_in(10).steps_per_sample(2).take(3) {
+    assert!(!grid.has_unknown_slot());
+    assert!(grid.solve().next().is_some());
+  }
+}
+
+#[test]
+fn uniform_4x4_distribution() {
+  // There are 288 complete 4x4 grids, each of them should be equally likely
+  let num_samples = 288 * 30;
+  let mut counts = HashMap::new();
+  for grid in UniformSampler::<Board4x4>::with_seed(2).steps_per_sample(3).take(num_samples) {
+    *counts.entry(grid).or_insert(0) += 1;
+  }
+  assert_eq!(counts.len(), 288);
+
+  // Chi-squared test with 287 degrees of freedom
+  let expected = num_samples as f64 / 288.0;
+  let chi2 : f64 = counts.values().map(|c| (*c as f64 - expected).powi(2) / expected).sum();
+  assert!(chi2 < 400.0, "chi2 = {}", chi2);
+}
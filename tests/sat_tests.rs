@@ -0,0 +1,109 @@
+#![cfg(feature = "sat")]
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use sudoku_rs::*;
+
+fn sat_ctx() -> SolvingContext {
+  SolvingContext::deterministic().with_backend(SolverBackend::Sat(CnfOptions::default()))
+}
+
+/// Check random 3-SAT formulas against a brute force search
+#[test]
+fn random_formulas() {
+  let mut rng = StdRng::seed_from_u64(1);
+  for _ in 0..200 {
+    let num_variables = rng.gen_range(1, 12);
+    let clauses = (0..rng.gen_range(1, 60)).map(|_| {
+      (0..3).map(|_| {
+        let var = rng.gen_range(1, num_variables as i32 + 1);
+        if rng.gen() { var } else { -var }
+      }).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    let satisfies = |model: &[i32]| clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit)));
+    let brute_force = (0..1u32 << num_variables).any(|bits| {
+      let model = (0..num_variables).map(|v| if bits >> v & 1 == 1 { v as i32 + 1 } else { -(v as i32) - 1 }).collect::<Vec<_>>();
+      satisfies(&model)
+    });
+    let mut solver = SatSolver::from_cnf(&Cnf { num_variables, clauses: clauses.clone() });
+    match solver.solve() {
+      Some(model) => assert!(brute_force && satisfies(&model)),
+      None => assert!(!brute_force),
+    }
+  }
+}
+
+#[test]
+fn enumerate_4x4() {
+  let mut solutions = Board4x4::default().solve_with_ctx(sat_ctx()).collect::<Vec<_>>();
+  solutions.sort();
+  solutions.dedup();
+  assert_eq!(solutions.len(), 288);
+}
+
+#[test]
+fn solve_hard_9x9() {
+  let board = Board9x9::from_u8_vec(&[
+    8, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 3, 6, 0, 0, 0, 0, 0,
+    0, 7, 0, 0, 9, 0, 2, 0, 0,
+    0, 5, 0, 0, 0, 7, 0, 0, 0,
+    0, 0, 0, 0, 4, 5, 7, 0, 0,
+    0, 0, 0, 1, 0, 0, 0, 3, 0,
+    0, 0, 1, 0, 0, 0, 0, 6, 8,
+    0, 0, 8, 5, 0, 0, 0, 1, 0,
+    0, 9, 0, 0, 0, 0, 4, 0, 0,
+  ]);
+  let expected = board.solve_with_ctx(SolvingContext::deterministic()).collect::<Vec<_>>();
+  let mut solutions = board.solve_with_ctx(sat_ctx());
+  assert_eq!(solutions.by_ref().collect::<Vec<_>>(), expected);
+  assert_eq!(solutions.status(), SearchStatus::Complete);
+}
+
+#[test]
+fn diagonal_constraints() {
+  let ctx = SolvingContext::random_with_seed(2).with_backend(SolverBackend::Sat(CnfOptions { diagonals: true }));
+  for solution in Board9x9::default().solve_with_ctx(ctx).take(5) {
+    let mut major = (0..9).map(|i| solution.to_u8_vec()[i * 9 + i]).collect::<Vec<_>>();
+    let mut minor = (0..9).map(|i| solution.to_u8_vec()[i * 9 + 8 - i]).collect::<Vec<_>>();
+    major.sort_unstable();
+    minor.sort_unstable();
+    assert_eq!(major, (1..=9).collect::<Vec<_>>());
+    assert_eq!(minor, (1..=9).collect::<Vec<_>>());
+  }
+}
+
+#[test]
+fn variant_constraints() {
+  let puzzle = VariantPuzzle::new(Board4x4::default())
+    .constraint(Constraint::Cage { slots: vec![(0, 0), (0, 1), (1, 0)], sum: Some(8) })
+    .constraint(Constraint::Arrow { circle: vec![(3, 3)], arrow: vec![(2, 2), (3, 2)] })
+    .constraint(Constraint::Thermometer(vec![(2, 0), (3, 0)]));
+  let mut expected = puzzle.solve().map(|solution| solution.to_line()).collect::<Vec<_>>();
+  let mut solutions = puzzle.solve_with_ctx(sat_ctx()).map(|solution| solution.to_line()).collect::<Vec<_>>();
+  expected.sort();
+  solutions.sort();
+  assert!(!expected.is_empty());
+  assert_eq!(solutions, expected);
+
+  // The diagonals of the backend options are added to the puzzle
+  let ctx = SolvingContext::random_with_seed(3).with_backend(SolverBackend::Sat(CnfOptions { diagonals: true }));
+  let diagonals = puzzle.clone()
+    .constraint(Constraint::Diagonal(Diagonal::Negative))
+    .constraint(Constraint::Diagonal(Diagonal::Positive));
+  for solution in puzzle.solve_with_ctx(ctx) {
+    assert!(diagonals.is_valid(&solution));
+  }
+}
+
+#[test]
+fn sat_checkpoint() {
+  let all = Board4x4::default().solve_with_ctx(sat_ctx()).collect::<Vec<_>>();
+  let mut solutions = Board4x4::default().solve_with_ctx(sat_ctx());
+  let mut found = solutions.by_ref().take(100).collect::<Vec<_>>();
+  let resumed = BoardSolutions::<Board4x4>::from_checkpoint(&solutions.checkpoint(), sat_ctx()).unwrap();
+  found.extend(resumed);
+  found.sort();
+  let mut all = all;
+  all.sort();
+  assert_eq!(found, all);
+}
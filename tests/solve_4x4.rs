@@ -35,4 +35,11 @@ fn solve_pre_filled_4x4_digged() {
   for solution in board.solve() {
     println!("{:?}", solution);
   }
-}
\ No newline at end of file
+}
+
+#[test]
+fn deterministic_enumeration_order() {
+  // The largest possible answer of a slot is tried first
+  let mut solutions = Board4x4::default().solve_with_ctx(SolvingContext::deterministic());
+  assert_eq!(solutions.next().unwrap().to_line(), "4321214334121234");
+}
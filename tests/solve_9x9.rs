@@ -37,4 +37,49 @@ fn solve_pre_filled_9x9() {
   for solution in board.solve() {
     println!("{:?}", solution);
   }
-}
\ No newline at end of file
+}
+
+#[test]
+fn solve_stats_9x9() {
+  let board = Board9x9::from_u8_vec(&[
+    5, 3, 0, 0, 7, 0, 0, 0, 0,
+    6, 0, 0, 1, 9, 5, 0, 0, 0,
+    0, 9, 8, 0, 0, 0, 0, 6, 0,
+    8, 0, 0, 0, 6, 0, 0, 0, 3,
+    4, 0, 0, 8, 0, 3, 0, 0, 1,
+    7, 0, 0, 0, 2, 0, 0, 0, 6,
+    0, 6, 0, 0, 0, 0, 2, 8, 0,
+    0, 0, 0, 4, 1, 9, 0, 0, 5,
+    0, 0, 0, 0, 8, 0, 0, 7, 9,
+  ]);
+  let mut solutions = board.solve_with_ctx(SolvingContext::deterministic().with_stats(true));
+  assert!(solutions.next().is_some());
+  assert!(solutions.next().is_none());
+  let stats = solutions.stats();
+  assert_eq!(stats.solutions, 1);
+  assert!(stats.nodes >= 1);
+  assert!(stats.forced_placements > 0);
+  assert!(stats.nodes > stats.backtracks);
+
+  // Without detailed statistics, only the counters are kept
+  let mut solutions = board.solve_with_ctx(SolvingContext::deterministic());
+  assert!(solutions.next().is_some());
+  assert_eq!(solutions.stats().forced_placements, 0);
+  assert_eq!(solutions.stats().elapsed, std::time::Duration::ZERO);
+}
+
+#[test]
+fn solve_stats_count_branches_taken() {
+  // Every node but the first one is a branch taken on a guess
+  let mut solutions = Board9x9::default().solve_with_ctx(SolvingContext::deterministic());
+  assert!(solutions.next().is_some());
+  let stats = solutions.stats();
+  assert_eq!(stats.guesses, stats.nodes - 1);
+  assert_eq!(stats.backtracks, 0);
+
+  // Easy boards are solved by propagation alone
+  let board = Board9x9::from_line("..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..").unwrap();
+  let mut solutions = board.solve_with_ctx(SolvingContext::deterministic());
+  assert!(solutions.next().is_some());
+  assert_eq!(solutions.stats().guesses, 0);
+}
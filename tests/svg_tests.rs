@@ -0,0 +1,34 @@
+use sudoku_rs::*;
+
+fn puzzle() -> Board4x4 {
+  Board4x4::from_u8_vec(&[1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2])
+}
+
+#[test]
+fn svg_givens_and_solved_slots() {
+  let solution = puzzle().solve_with_ctx(SolvingContext::deterministic()).next().unwrap();
+  let svg = SvgRenderer::new().render_solution(&puzzle(), &solution);
+  assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+  assert_eq!(svg.matches("<text").count(), 16);
+  assert_eq!(svg.matches("font-weight=\"bold\"").count(), 4);
+  // 5 vertical and 5 horizontal lines, 3 of each being block boundaries
+  assert_eq!(svg.matches("<line").count(), 10);
+  assert_eq!(svg.matches("stroke-width=\"2.5\"").count(), 6);
+}
+
+#[test]
+fn svg_candidates() {
+  let svg = SvgRenderer::new().candidates(true).render(&puzzle());
+  let num_candidates = puzzle().unknown_slots().iter().map(|slot| puzzle().possible_answers(slot).count()).sum::<usize>();
+  assert_eq!(svg.matches("<text").count(), 4 + num_candidates);
+}
+
+#[test]
+fn svg_decorations() {
+  let cage = Decoration::Cage { slots: vec![(0, 0), (0, 1), (1, 1)], sum: Some(7) };
+  let svg = SvgRenderer::new().decoration(Decoration::Diagonals).decoration(cage).render(&Board4x4::default());
+  // 10 grid lines, 2 diagonals, and the 8 sides of the cage outline
+  assert_eq!(svg.matches("<line").count(), 10 + 2 + 8);
+  assert_eq!(svg.matches("stroke-dasharray").count(), 8);
+  assert!(svg.contains(">7</text>"));
+}
@@ -0,0 +1,74 @@
+use sudoku_rs::{*, utils::*};
+
+#[test]
+fn trace_steps_are_justified() {
+  let question = Board9x9::from_line("800000000003600000070090200050007000000045700000100030001000068008500010090000400").unwrap();
+  let trace = question.solve_with_trace();
+  let solution = question.solve_with_ctx(SolvingContext::deterministic()).next();
+  assert_eq!(trace.solution, solution);
+  assert_eq!(trace.boards().last(), solution.as_ref());
+  assert!(trace.guesses() > 0);
+  assert!(trace.steps.iter().any(|step| step.reason == StepReason::Backtrack));
+
+  // Every single is forced on the board right before it
+  let boards = trace.boards();
+  let before = std::iter::once(Board9x9::default()).chain(boards.iter().cloned());
+  for (step, board) in trace.steps.iter().zip(before) {
+    let candidates = board.possible_answers(&step.slot).elements().into_iter().map(u8::from).collect::<Vec<_>>();
+    match step.reason {
+      StepReason::Given => assert_eq!(*question.get(&step.slot), step.value.into()),
+      StepReason::NakedSingle => assert_eq!(candidates, vec![step.value]),
+      StepReason::HiddenSingle | StepReason::Guess => assert!(candidates.contains(&step.value)),
+      StepReason::Backtrack => {},
+    }
+  }
+}
+
+#[test]
+fn trace_to_target_solution() {
+  // Many solutions, the target not being the first one of the search
+  let question = Board9x9::from_line("800000000003600000070090200050007000000045700000100030001000068008500010090000000").unwrap();
+  let solutions = question.solve_with_ctx(SolvingContext::deterministic()).take(3).collect::<Vec<_>>();
+  assert_eq!(solutions.len(), 3);
+  for target in &solutions {
+    let trace = question.solve_with_trace_to(target);
+    assert_eq!(trace.solution.as_ref(), Some(target));
+    assert_eq!(trace.boards().last(), Some(target));
+  }
+
+  // Same trace as without a target for a unique solution
+  let unique = Board9x9::from_line("800000000003600000070090200050007000000045700000100030001000068008500010090000400").unwrap();
+  let solution = unique.solve().next().unwrap();
+  assert_eq!(unique.solve_with_trace_to(&solution), unique.solve_with_trace());
+}
+
+#[test]
+fn trace_of_unsatisfiable_board() {
+  // Two 1s in the first row
+  let board = Board4x4::from_u8_vec(&[1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+  let trace = board.solve_with_trace();
+  assert_eq!(trace.solution, None);
+  assert!(trace.steps.iter().all(|step| step.reason == StepReason::Given));
+  assert_eq!("hidden_single".parse::<StepReason>(), Ok(StepReason::HiddenSingle));
+}
+
+#[test]
+fn trace_output_record() {
+  let path = std::env::temp_dir().join("sudoku_rs_trace_output_record.ndjson");
+  let filename = Some(path.to_str().unwrap().to_string());
+  let solution = Board4x4::from_u8_vec(&[1, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 1]);
+  let question = Board4x4::from_u8_vec(&[1, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 0]);
+  let trace = question.solve_with_trace();
+
+  let mut output = Output::new_with_format(&filename, OutputFormat::NdJson).unwrap();
+  output.output_board_with_trace(&question, &solution, &trace.steps).unwrap();
+  let contents = std::fs::read_to_string(&path).unwrap();
+  assert!(contents.trim_end().ends_with(",[3,3,1,\"naked_single\"]]}"));
+
+  // Readers of the records ignore the trace
+  let records = read_records(path.to_str().unwrap()).unwrap();
+  assert_eq!(records[0].answer, Some(solution.to_u8_vec()));
+
+  let mut output = Output::new_with_format(&filename, OutputFormat::Binary).unwrap();
+  assert!(output.output_board_with_trace(&question, &solution, &trace.steps).is_err());
+}
@@ -0,0 +1,59 @@
+use sudoku_rs::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+fn is_valid<B: Board>(board: &B) -> bool {
+  B::slots().all(|slot| {
+    let elem = *board.get(&slot);
+    let mut b = board.clone();
+    b.set(&slot, B::Element::default());
+    elem.is_answer() && b.possible_answers(&slot).elements().contains(&elem)
+  })
+}
+
+#[test]
+fn transforms_preserve_validity() {
+  let board = Board9x9::default().solve_with_ctx(SolvingContext::random_with_seed(1)).next().unwrap();
+  let transforms = vec![
+    Transform::relabel::<Board9x9>(&[9, 8, 7, 6, 5, 4, 3, 2, 1]),
+    Transform::swap_rows::<Board9x9>(3, 5),
+    Transform::swap_bands::<Board9x9>(0, 2),
+    Transform::swap_columns::<Board9x9>(6, 7),
+    Transform::swap_stacks::<Board9x9>(1, 2),
+    Transform::transpose::<Board9x9>(),
+    Transform::rotate::<Board9x9>(),
+  ];
+  for transform in transforms {
+    assert!(is_valid(&transform.apply(&board)));
+  }
+}
+
+#[test]
+fn rotate_4x4() {
+  let board = sudoku4x4![
+    1, 2, 3, 4;
+    3, 4, 1, 2;
+    4, 3, 2, 1;
+    2, 1, 4, 3;
+  ];
+  let rotated = board.transform(&Transform::rotate::<Board4x4>());
+  assert_eq!(rotated, sudoku4x4![
+    2, 4, 3, 1;
+    1, 3, 4, 2;
+    4, 2, 1, 3;
+    3, 1, 2, 4;
+  ]);
+}
+
+#[test]
+fn compose_and_invert_random_transforms() {
+  let mut rng = StdRng::seed_from_u64(7);
+  let board = Board9x9::default().solve_with_ctx(SolvingContext::random_with_seed(2)).next().unwrap();
+  for _ in 0..20 {
+    let t1 = Transform::random::<Board9x9, _>(&mut rng);
+    let t2 = Transform::random::<Board9x9, _>(&mut rng);
+    let composed = t1.then(&t2);
+    assert_eq!(composed.apply(&board), t2.apply(&t1.apply(&board)));
+    assert_eq!(composed.inverse().apply(&composed.apply(&board)), board);
+    assert!(is_valid(&composed.apply(&board)));
+  }
+}
@@ -0,0 +1,135 @@
+use sudoku_rs::{*, utils::*};
+
+const PAYLOAD : &str = "N4IgzglgXgpiBcAWANCALhNAbO8QBUIA7ATxFQHMAnCAEwQG0HQA3AQywFdcBGSiFjCII0VbgF9kwSdKniAusmYyVcxcrmbp62bpkh2XXAGYFikLQhsKAeyIcAtCLExUAawhYcVAMbXczCA+MF5gjCAASgBMAMI85JGxUSDmhtwIIADsIAqoaAAWMFQAtjbFMGhFjKBYxDBh8EyRiHEJES3J8vK5IGxUVDYA7tVBIVgNDJHGMYgpqLVE9YyTEdOzqKsxyRvT8V09bEQYbkQQFPloztyoAB4sjOaVN5eNoMGh4RE8rakc6Xj5CA5bpAA=";
+
+#[test]
+fn variant_constraints() {
+  // Both diagonals on a 4x4 board
+  let puzzle = VariantPuzzle::new(Board4x4::default())
+    .constraint(Constraint::Diagonal(Diagonal::Positive))
+    .constraint(Constraint::Diagonal(Diagonal::Negative));
+  let solutions = puzzle.solve().collect::<Vec<_>>();
+  assert!(!solutions.is_empty());
+  assert!(solutions.len() < Board4x4::default().solve().count());
+  for solution in &solutions {
+    assert!(puzzle.is_valid(solution));
+    let digits = solution.to_u8_vec();
+    let mut diagonal = (0..4).map(|i| digits[i * 4 + i]).collect::<Vec<_>>();
+    diagonal.sort_unstable();
+    assert_eq!(diagonal, vec![1, 2, 3, 4]);
+  }
+
+  // A killer cage, an arrow with a two digit circle, and a thermometer
+  let puzzle = VariantPuzzle::new(Board9x9::default())
+    .constraint(Constraint::Cage { slots: vec![(0, 0), (0, 1)], sum: Some(3) })
+    .constraint(Constraint::Arrow { circle: vec![(8, 0), (8, 1)], arrow: vec![(7, 0), (7, 1), (7, 2), (6, 2)] })
+    .constraint(Constraint::Thermometer(vec![(4, 4), (4, 5), (4, 6), (4, 7), (4, 8)]));
+  let solution = puzzle.solve().next().unwrap();
+  assert!(puzzle.is_valid(&solution));
+  let digits = solution.to_u8_vec();
+  assert_eq!(digits[0] + digits[1], 3);
+  assert_eq!(digits[72] * 10 + digits[73], digits[63] + digits[64] + digits[65] + digits[56]);
+  assert!(digits[40..45].windows(2).all(|pair| pair[0] < pair[1]));
+
+  // Givens breaking a rule have no solution
+  let mut board = Board4x4::default();
+  board.set(&(0, 0), 1.into());
+  board.set(&(1, 1), 1.into());
+  assert_eq!(VariantPuzzle::new(board).constraint(Constraint::Diagonal(Diagonal::Negative)).solve().next(), None);
+}
+
+#[test]
+fn variant_solving_context() {
+  let puzzle = VariantPuzzle::new(Board9x9::default())
+    .constraint(Constraint::Cage { slots: vec![(0, 0), (0, 1), (1, 0)], sum: Some(7) })
+    .constraint(Constraint::Arrow { circle: vec![(8, 0), (8, 1)], arrow: vec![(7, 0), (7, 1), (7, 2), (6, 2)] })
+    .constraint(Constraint::Thermometer(vec![(4, 4), (4, 5), (4, 6)]));
+  for seed in 0..10 {
+    let mut solutions = puzzle.solve_with_ctx(SolvingContext::random_with_seed(seed).with_stats(true));
+    let solution = solutions.next().unwrap();
+    assert!(puzzle.is_valid(&solution));
+    assert!(solution.to_u8_vec().iter().all(|d| *d != 0));
+    assert_eq!(solutions.stats().solutions, 1);
+  }
+
+  // The budget stops the search, telling it apart from having no solution
+  let ctx = SolvingContext::deterministic().with_budget(SearchBudget::unlimited().with_max_nodes(3));
+  let mut solutions = puzzle.solve_with_ctx(ctx);
+  assert_eq!(solutions.by_ref().nth(100), None);
+  assert_eq!(solutions.status(), SearchStatus::BudgetExhausted(BudgetExhausted::Nodes));
+}
+
+#[test]
+fn variant_cnf() {
+  let puzzle = VariantPuzzle::new(Board4x4::default())
+    .constraint(Constraint::Diagonal(Diagonal::Negative))
+    .constraint(Constraint::Thermometer(vec![(0, 0), (0, 1), (1, 1)]));
+  let cnf = puzzle.to_cnf();
+  let satisfies = |board: &Board4x4| {
+    let digits = board.to_u8_vec();
+    cnf.clauses.iter().all(|clause| clause.iter().any(|literal| {
+      let k = (literal.unsigned_abs() as usize - 1) / 4;
+      (digits[k] as i32 == (literal.abs() - 1) % 4 + 1) == (*literal > 0)
+    }))
+  };
+
+  // The boards satisfying the encoding are the solutions of the puzzle
+  let boards = Board4x4::default().solve().collect::<Vec<_>>();
+  let solutions = boards.iter().filter(|board| puzzle.is_valid(board)).collect::<Vec<_>>();
+  assert!(!solutions.is_empty());
+  assert_eq!(boards.iter().filter(|board| satisfies(board)).collect::<Vec<_>>(), solutions);
+
+  // Plain boards keep the encoding of `Cnf::encode`, diagonals included
+  let options = CnfOptions { diagonals: true };
+  let puzzle = options.constraints().into_iter().fold(VariantPuzzle::new(Board9x9::default()), VariantPuzzle::constraint);
+  assert_eq!(puzzle.to_cnf(), Board9x9::default().to_cnf_with_options(&options));
+}
+
+#[test]
+fn lz_string_payloads() {
+  assert_eq!(decompress_from_base64("BYUwNmD2Q===").unwrap(), "hello");
+  assert_eq!(decompress_from_base64("N4IgzglgXgpiBcAWAvkA").unwrap(), r#"{"size":4}"#);
+  assert_eq!(decompress_from_base64("").unwrap(), "");
+  assert!(decompress_from_base64("N4Ig*").is_err());
+
+  // Links and bare payloads give the same puzzle
+  let link = format!("https://www.f-puzzles.com/?load={}", PAYLOAD);
+  assert!(is_fpuzzles(&link) && is_fpuzzles(PAYLOAD));
+  assert_eq!(parse_fpuzzles(&link).unwrap(), parse_fpuzzles(PAYLOAD).unwrap());
+  let link = format!("https://sudokupad.app/fpuzzles{}", PAYLOAD.replace('+', "%2B"));
+  assert_eq!(parse_fpuzzles(&link).unwrap(), parse_fpuzzles(PAYLOAD).unwrap());
+}
+
+#[test]
+fn fpuzzles_import() {
+  let puzzle = parse_fpuzzles(PAYLOAD).unwrap();
+  assert_eq!(puzzle.title.as_deref(), Some("Tiny"));
+  assert_eq!(puzzle.size, 4);
+
+  // Values that are not given are the solver's progress
+  assert_eq!(puzzle.givens, vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+  assert_eq!(puzzle.constraints, vec![
+    Constraint::Diagonal(Diagonal::Negative),
+    Constraint::Cage { slots: vec![(1, 0), (1, 1)], sum: Some(7) },
+    Constraint::Thermometer(vec![(3, 0), (3, 1)]),
+    Constraint::Arrow { circle: vec![(2, 3)], arrow: vec![(2, 1), (2, 0)] },
+  ]);
+
+  // Rules left empty are not reported, nor are cosmetic ones
+  assert_eq!(puzzle.unsupported, vec!["antiknight".to_string()]);
+  let variant = puzzle.to_variant::<Board4x4>().unwrap();
+  let solutions = variant.solve().collect::<Vec<_>>();
+  assert_eq!(solutions.len(), 1);
+  assert_eq!(solutions[0].to_line(), "1234432121433412");
+  assert!(puzzle.to_variant::<Board9x9>().is_err());
+
+  // Irregular regions
+  let rows = (0..4).map(|i| {
+    let cells = vec![format!(r#"{{"region": {}}}"#, i); 4];
+    format!("[{}]", cells.join(","))
+  }).collect::<Vec<_>>();
+  let json = format!(r#"{{"size": 4, "grid": [{}]}}"#, rows.join(","));
+  let puzzle = parse_fpuzzles(&json).unwrap();
+  assert!(matches!(&puzzle.constraints[..], [Constraint::Regions(regions)] if regions[1] == vec![(1, 0), (1, 1), (1, 2), (1, 3)]));
+  assert!(parse_fpuzzles(r#"{"size": 4, "grid": []}"#).is_err());
+}